@@ -0,0 +1,61 @@
+use serde::Deserialize;
+
+use crate::fetch;
+
+/// air quality index
+#[derive(Deserialize, Debug)]
+pub struct Index {
+    /// Air Quality Index (1 = Good, 2 = Fair, 3 = Moderate, 4 = Poor, 5 = Very Poor)
+    pub aqi: u8,
+}
+
+/// concentration of polluting gases and particulates
+#[derive(Deserialize, Debug)]
+pub struct Components {
+    /// Concentration of NO2 (Nitrogen dioxide), μg/m3
+    pub no2: f64,
+    /// Concentration of O3 (Ozone), μg/m3
+    pub o3: f64,
+    /// Concentration of PM2.5 (Fine particles matter), μg/m3
+    pub pm2_5: f64,
+    /// Concentration of PM10 (Coarse particulate matter), μg/m3
+    pub pm10: f64,
+}
+
+/// one entry of the air pollution data
+#[derive(Deserialize, Debug)]
+pub struct Entry {
+    /// air quality index
+    pub main: Index,
+    /// concentration of polluting gases and particulates
+    pub components: Components,
+}
+
+/// current air pollution like described [here](https://openweathermap.org/api/air-pollution)
+#[derive(Deserialize, Debug)]
+pub struct AirPollution {
+    /// current air pollution (one entry)
+    pub list: Vec<Entry>,
+}
+
+/// Receiver object you get from `init()` and have to handle to `fetch::update()`.
+pub type Receiver = fetch::Receiver<AirPollution>;
+
+/// Spawns a thread which fetches the current air pollution periodically.
+/// #### Parameters
+/// - `latitude`: geo location, latitude
+/// - `longitude`: geo location, longitude
+/// - `api_key`: OpenWeatherMap API key
+/// - `poll_mins`: duration of poll period in minutes
+pub fn init(latitude: f64, longitude: f64, api_key: &str, poll_mins: u64) -> Receiver {
+    fetch::init(
+        format!(
+            "{}/2.5/air_pollution?lat={}&lon={}&appid={}",
+            fetch::OWM_URL,
+            latitude,
+            longitude,
+            api_key
+        ),
+        poll_mins,
+    )
+}
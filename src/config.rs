@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+use toml_edit::{Document, Item, Value};
+
+/// get default location of the configuration file (`$XDG_CONFIG_HOME/i3owm/config.toml`)
+pub fn default_path() -> Option<PathBuf> {
+    let dir = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(dir.join("i3owm").join("config.toml"))
+}
+
+/// find path of the configuration file given by `--config` within the program arguments
+/// #### Parameters
+/// - `args`: program arguments
+pub fn find_path(args: &[String]) -> Option<PathBuf> {
+    for (i, arg) in args.iter().enumerate() {
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+        if arg == "--config" {
+            return args.get(i + 1).map(PathBuf::from);
+        }
+    }
+    None
+}
+
+/// convert the content of a configuration file into program arguments
+///
+/// Every entry `name = value` becomes an option `--name=value`.
+/// Arrays repeat the option for every item and booleans become flags if they are `true`.
+/// Every entry `key = value` of a table `[name]` becomes an option `--name=key=value`.
+/// #### Parameters
+/// - `config`: content of a configuration file in TOML
+/// #### Return value
+/// - ⇒ `Ok(Vec<String>)`: program arguments
+/// - ⇒ `Err(String)`: error message if the content could not be parsed
+pub fn to_args(config: &str) -> Result<Vec<String>, String> {
+    fn push(name: &str, value: &Value, args: &mut Vec<String>) -> Result<(), String> {
+        match value {
+            Value::String(s) => args.push(format!("--{}={}", name, s.value())),
+            Value::Integer(i) => args.push(format!("--{}={}", name, i.value())),
+            Value::Float(f) => args.push(format!("--{}={}", name, f.value())),
+            Value::Boolean(b) => {
+                if *b.value() {
+                    args.push(format!("--{}", name))
+                }
+            }
+            Value::Array(a) => {
+                for v in a.iter() {
+                    push(name, v, args)?;
+                }
+            }
+            _ => return Err(format!("unsupported value of `{}` in configuration", name)),
+        }
+        Ok(())
+    }
+    let doc = Document::parse(config).map_err(|e| e.to_string())?;
+    let mut args = Vec::new();
+    for (name, item) in doc.as_table().iter() {
+        match item {
+            Item::Value(Value::InlineTable(t)) => {
+                for (key, v) in t.iter() {
+                    push(&format!("{}={}", name, key), v, &mut args)?;
+                }
+            }
+            Item::Value(v) => push(name, v, &mut args)?,
+            Item::Table(t) => {
+                for (key, item) in t.iter() {
+                    match item {
+                        Item::Value(v) => push(&format!("{}={}", name, key), v, &mut args)?,
+                        _ => {
+                            return Err(format!(
+                                "unsupported entry `{}.{}` in configuration",
+                                name, key
+                            ))
+                        }
+                    }
+                }
+            }
+            _ => return Err(format!("unsupported entry `{}` in configuration", name)),
+        }
+    }
+    Ok(args)
+}
+
+/// insert the options of the configuration file in front of the program arguments
+///
+/// The configuration file is taken from `--config` or from the default location if it exists.
+/// Options given at the command line will override those from the configuration file.
+/// #### Parameters
+/// - `args`: program arguments
+/// #### Return value
+/// - ⇒ `Ok(Vec<String>)`: program arguments including the options of the configuration file
+/// - ⇒ `Err(String)`: error message if the configuration file could not be read
+pub fn args(args: Vec<String>) -> Result<Vec<String>, String> {
+    let path = match find_path(&args) {
+        Some(path) => path,
+        None => match default_path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(args),
+        },
+    };
+    let config =
+        std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.to_string_lossy(), e))?;
+    let config = to_args(&config).map_err(|e| format!("{}: {}", path.to_string_lossy(), e))?;
+    let mut args = args.into_iter();
+    Ok(args.next().into_iter().chain(config).chain(args).collect())
+}
@@ -0,0 +1,94 @@
+use serde::de::DeserializeOwned;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Receiver object you get from `init()` and have to handle to `update()`.
+pub type Receiver<T> = mpsc::Receiver<Result<T, String>>;
+
+/// base URL of all OpenWeatherMap APIs
+pub const OWM_URL: &str = "http://api.openweathermap.org/data";
+
+/// Spawns a thread which fetches a json document from an URL periodically
+/// (like `openweathermap::init()` does for the current weather).
+/// #### Parameters
+/// - `url`: URL to fetch
+/// - `poll_mins`: Update interval:
+///     - `> 0`: duration of poll period in minutes
+///     - `= 0`: thread will terminate after the first successful update.
+///
+/// The thread also terminates if the API key is not authorized (`401` or `403`).
+/// #### Return value
+/// - `fetch::Receiver`: Handle this to `fetch::update()` to get the latest update.
+pub fn init<T: DeserializeOwned + Send + 'static>(url: String, poll_mins: u64) -> Receiver<T> {
+    // fork thread that continuously fetches updates every <poll_mins> minutes
+    let period = Duration::from_secs(60 * poll_mins);
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        tx.send(Err(openweathermap::LOADING.to_string()))
+            .unwrap_or(());
+        loop {
+            match reqwest::blocking::get(&url) {
+                Ok(response) => match response.status() {
+                    reqwest::StatusCode::OK => {
+                        match serde_json::from_str::<T>(&response.text().unwrap_or_default()) {
+                            Ok(t) => {
+                                tx.send(Ok(t)).unwrap_or(());
+                                if period == Duration::new(0, 0) {
+                                    break;
+                                }
+                                thread::sleep(period);
+                                continue;
+                            }
+                            Err(e) => tx.send(Err(e.to_string())).unwrap_or(()),
+                        }
+                    }
+                    // the key is not allowed to use this API (e.g. One Call 3.0 needs a separate
+                    // subscription) so retrying would only waste requests
+                    reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                        tx.send(Err(response.status().to_string())).unwrap_or(());
+                        break;
+                    }
+                    _ => tx.send(Err(response.status().to_string())).unwrap_or(()),
+                },
+                Err(e) => tx.send(Err(e.to_string())).unwrap_or(()),
+            }
+            // wait a minute before we retry after any error
+            thread::sleep(Duration::from_secs(60));
+        }
+    });
+    // return receiver that provides the updates
+    return rx;
+}
+
+/// Get the update that the spawned thread could fetch.
+/// #### Parameters
+/// - `receiver`: the *channel receiver* from preceded call to `fetch::init()`
+/// #### Return value
+/// - ⇒ `None`: No update available
+/// - ⇒ `Some(Result)`: Update available
+///     - ⇒ `Ok(T)`: parsed json document
+///     - ⇒ `Err(String)`: Error message about any occured http or json issue
+pub fn update<T>(receiver: &Receiver<T>) -> Option<Result<T, String>> {
+    return receiver.try_recv().ok();
+}
+
+/// Get the latest update of an additional source (like the forecast) which must not hide
+/// the current weather if it fails.
+/// #### Parameters
+/// - `receiver`: the *channel receiver* from preceded call to `fetch::init()` (if started)
+/// - `name`: name of the source within error messages
+/// #### Return value
+/// - ⇒ `Some(T)`: parsed json document
+/// - ⇒ `None`: No update available, still loading or an error occured (which is written to *stderr*)
+pub fn latest<T>(receiver: &Option<Receiver<T>>, name: &str) -> Option<T> {
+    match update(receiver.as_ref()?)? {
+        Ok(t) => return Some(t),
+        Err(e) => {
+            if e != openweathermap::LOADING {
+                eprintln!("i3owm: {}: {}", name, e);
+            }
+            return None;
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use serde::Deserialize;
+
+use crate::fetch;
+
+/// one entry of the 5 day / 3 hour forecast
+#[derive(Deserialize, Debug)]
+pub struct Entry {
+    /// Time of forecasted data, unix, UTC
+    pub dt: i64,
+    /// detailed weather forecast
+    pub main: openweathermap::Main,
+    /// weather condition descriptions
+    pub weather: Vec<openweathermap::Weather>,
+    /// detailed wind forecast
+    pub wind: openweathermap::Wind,
+    /// Probability of precipitation (0..1)
+    pub pop: Option<f64>,
+    /// rain volume for the last 3 hours
+    pub rain: Option<openweathermap::Volume>,
+}
+
+/// forecast location
+#[derive(Deserialize, Debug)]
+pub struct City {
+    /// Shift in seconds from UTC
+    pub timezone: i64,
+}
+
+/// 5 day / 3 hour forecast like described [here](https://openweathermap.org/forecast5)
+#[derive(Deserialize, Debug)]
+pub struct Forecast {
+    /// forecast entries in steps of 3 hours
+    pub list: Vec<Entry>,
+    /// forecast location
+    pub city: City,
+}
+
+/// Receiver object you get from `init()` and have to handle to `fetch::update()`.
+pub type Receiver = fetch::Receiver<Forecast>;
+
+/// Spawns a thread which fetches the 5 day / 3 hour forecast periodically.
+/// #### Parameters
+/// - `latitude`: geo location, latitude
+/// - `longitude`: geo location, longitude
+/// - `units`: either `"standard"`, `"metric"` or `"imperial"`
+/// - `lang`: language code of weather descriptions
+/// - `api_key`: OpenWeatherMap API key
+/// - `poll_mins`: duration of poll period in minutes
+pub fn init(
+    latitude: f64,
+    longitude: f64,
+    units: &str,
+    lang: &str,
+    api_key: &str,
+    poll_mins: u64,
+) -> Receiver {
+    fetch::init(
+        format!(
+            "{}/2.5/forecast?lat={}&lon={}&units={}&lang={}&appid={}",
+            fetch::OWM_URL,
+            latitude,
+            longitude,
+            units,
+            lang,
+            api_key
+        ),
+        poll_mins,
+    )
+}
@@ -0,0 +1,341 @@
+use std::fmt;
+use std::iter::{Enumerate, Peekable};
+use std::str::FromStr;
+
+use crate::i3bar::Markup;
+use crate::keys;
+use crate::property::{Properties, Value};
+
+/// alignment of a value within its width
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// format specifier of a key like `{temp:.1}`, `{wind_speed:>3}` or `{humidity:02}`
+///
+/// Syntax is `[[fill]align][0][width][.precision]` like in Rust's `format!()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spec {
+    fill: char,
+    align: Option<Align>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+/// part of a parsed format string
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    /// text which is copied into the output as it is
+    Literal(String),
+    /// name of a key (without braces) which will be replaced by its value formatted by a specifier
+    Key(String, Spec),
+    /// segments which will only be rendered if the given key has a value
+    Section { key: String, segments: Vec<Segment> },
+}
+
+/// display format which was parsed once from a format string
+#[derive(Clone, Debug, PartialEq)]
+pub struct Format {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+/// characters of a format string together with their (zero based) column
+type Chars<'a> = Peekable<Enumerate<std::str::Chars<'a>>>;
+
+impl Format {
+    /// parse a format string into literal, key and section segments
+    ///
+    /// A section `{?key:...}` includes a format which will only be rendered if `key` has a value.
+    /// Sections can not be nested.
+    /// Literal braces are written as `{{` and `}}` (also within sections where a single `}` closes
+    /// the section).
+    /// #### Parameters
+    /// - `format`: format string including some of the available keys
+    /// #### Return value
+    /// - ⇒ `Ok(Format)`: parsed format
+    /// - ⇒ `Err(String)`: error message naming the unknown key or the unclosed brace and its column
+    pub fn parse(format: &str) -> Result<Format, String> {
+        let mut chars = format.chars().enumerate().peekable();
+        Ok(Format {
+            source: format.to_string(),
+            segments: parse_segments(&mut chars, None)?,
+        })
+    }
+
+    /// get the names of all keys used within this format (including those in sections)
+    pub fn keys(&self) -> Vec<&str> {
+        fn collect<'a>(segments: &'a [Segment], keys: &mut Vec<&'a str>) {
+            for segment in segments {
+                match segment {
+                    Segment::Literal(_) => (),
+                    Segment::Key(k, _) => keys.push(k),
+                    Segment::Section { key, segments } => {
+                        keys.push(key);
+                        collect(segments, keys);
+                    }
+                }
+            }
+        }
+        let mut keys = Vec::new();
+        collect(&self.segments, &mut keys);
+        return keys;
+    }
+
+    /// insert properties into format and escape them for the given markup
+    /// #### Parameters
+    /// - `props`: property map to get data to insert from
+    /// - `markup`: markup which values will be escaped for (literal text will not be escaped)
+    /// #### Return value
+    /// - formatted string
+    pub fn render(&self, props: &Properties, markup: Markup) -> String {
+        let mut result = String::new();
+        render_segments(&self.segments, props, markup, &mut result);
+        return result;
+    }
+}
+
+/// parse segments until end of format or until the closing brace of a section
+/// #### Parameters
+/// - `chars`: remaining characters of the format string
+/// - `section`: column of the opening brace if we are within a section
+fn parse_segments(chars: &mut Chars, section: Option<usize>) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    while let Some((column, c)) = chars.next() {
+        match c {
+            // '{{' is an escaped '{'
+            '{' if chars.next_if(|&(_, c)| c == '{').is_some() => literal.push('{'),
+            // '}}' within a section would be ambiguous with the end of a nested section
+            '{' if section.is_some() && chars.peek().map(|&(_, c)| c) == Some('?') => {
+                return Err(format!("nested section at column {}", column + 1))
+            }
+            '{' => {
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(parse_key(chars, column)?);
+            }
+            // '}}' is an escaped '}' (also within sections)
+            '}' if chars.next_if(|&(_, c)| c == '}').is_some() => literal.push('}'),
+            '}' if section.is_some() => {
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(literal));
+                }
+                return Ok(segments);
+            }
+            '}' => return Err(format!("unmatched `}}` at column {}", column + 1)),
+            _ => literal.push(c),
+        }
+    }
+    if let Some(column) = section {
+        return Err(format!("unclosed section at column {}", column + 1));
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    return Ok(segments);
+}
+
+/// parse a key or section after its opening brace
+/// #### Parameters
+/// - `chars`: remaining characters of the format string
+/// - `column`: column of the opening brace
+fn parse_key(chars: &mut Chars, column: usize) -> Result<Segment, String> {
+    let section = chars.next_if(|&(_, c)| c == '?').is_some();
+    // collect key name until closing brace or until colon
+    let mut key = String::new();
+    let mut spec: Option<String> = None;
+    loop {
+        match chars.next() {
+            Some((_, '}')) if section => {
+                return Err(format!("missing `:` in section at column {}", column + 1))
+            }
+            Some((_, '}')) => break,
+            Some((_, ':')) if section => break,
+            // collect format specifier until closing brace
+            Some((_, ':')) if spec.is_none() => spec = Some(String::new()),
+            Some((_, c)) => match spec {
+                Some(ref mut spec) => spec.push(c),
+                None => key.push(c),
+            },
+            None => return Err(format!("unclosed key at column {}", column + 1)),
+        }
+    }
+    if !keys::is_known(&key) {
+        return Err(format!(
+            "unknown key `{{{}}}` at column {}{}",
+            key,
+            column + 1,
+            keys::hint(&key)
+        ));
+    }
+    // '{iss_space}' is a short cut for '{?iss: }' so it stands for 'iss' within sections too
+    if key == "iss_space" {
+        if spec.is_some() {
+            return Err(format!(
+                "`{{iss_space}}` takes no format specifier at column {}",
+                column + 1
+            ));
+        }
+        if !section {
+            return Ok(Segment::Section {
+                key: "iss".to_string(),
+                segments: vec![Segment::Literal(" ".to_string())],
+            });
+        }
+        key = "iss".to_string();
+    }
+    if section {
+        return Ok(Segment::Section {
+            key,
+            segments: parse_segments(chars, Some(column))?,
+        });
+    }
+    let spec = match spec {
+        Some(spec) => match Spec::parse(&spec) {
+            Some(s) => s,
+            None => {
+                return Err(format!(
+                    "invalid format specifier `{}` at column {}",
+                    spec,
+                    column + 1
+                ))
+            }
+        },
+        None => Spec::default(),
+    };
+    return Ok(Segment::Key(key, spec));
+}
+
+/// render segments and append them to the result
+fn render_segments(segments: &[Segment], props: &Properties, markup: Markup, result: &mut String) {
+    for segment in segments {
+        match segment {
+            Segment::Literal(l) => result.push_str(l),
+            Segment::Key(k, spec) => {
+                if let Some(v) = props.get(k) {
+                    result.push_str(&markup.escape(&spec.apply(v)));
+                }
+            }
+            Segment::Section { key, segments } => {
+                // skip section if key has no value
+                if props.has_value(key) {
+                    render_segments(segments, props, markup, result);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+    fn from_str(format: &str) -> Result<Format, String> {
+        Format::parse(format)
+    }
+}
+
+impl Default for Spec {
+    fn default() -> Spec {
+        Spec {
+            fill: ' ',
+            align: None,
+            zero: false,
+            width: 0,
+            precision: None,
+        }
+    }
+}
+
+impl Spec {
+    /// parse a format specifier (the part behind the colon)
+    /// #### Return value
+    /// - ⇒ `Some(Spec)`: parsed specifier
+    /// - ⇒ `None`: if specifier is invalid
+    pub fn parse(spec: &str) -> Option<Spec> {
+        fn align(c: char) -> Option<Align> {
+            match c {
+                '<' => Some(Align::Left),
+                '^' => Some(Align::Center),
+                '>' => Some(Align::Right),
+                _ => None,
+            }
+        }
+        fn number(chars: &mut Peekable<std::str::Chars>) -> Option<usize> {
+            let mut digits = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                digits.push(c);
+            }
+            digits.parse().ok()
+        }
+        let mut result = Spec::default();
+        let c: Vec<char> = spec.chars().take(2).collect();
+        let mut chars = spec.chars().peekable();
+        // fill character is only allowed in front of an alignment
+        if c.len() == 2 && align(c[1]).is_some() {
+            result.fill = c[0];
+            result.align = align(c[1]);
+            chars.nth(1);
+        } else if !c.is_empty() && align(c[0]).is_some() {
+            result.align = align(c[0]);
+            chars.next();
+        }
+        result.zero = chars.next_if_eq(&'0').is_some();
+        result.width = number(&mut chars).unwrap_or(0);
+        if chars.next_if_eq(&'.').is_some() {
+            result.precision = Some(number(&mut chars)?);
+        }
+        match chars.next() {
+            Some(_) => None,
+            None => Some(result),
+        }
+    }
+
+    /// format a value
+    /// #### Parameters
+    /// - `value`: value to format
+    /// #### Return value
+    /// - formatted value
+    pub fn apply(&self, value: &Value) -> String {
+        let (text, numeric) = match (value, self.precision) {
+            (Value::Number { value, .. }, Some(precision)) => {
+                (format!("{:.*}", precision, value), true)
+            }
+            (Value::Number { .. }, None) => (value.to_string(), true),
+            (_, Some(precision)) => (value.to_string().chars().take(precision).collect(), false),
+            (_, None) => (value.to_string(), false),
+        };
+        let len = text.chars().count();
+        if len >= self.width {
+            return text;
+        }
+        let pad = self.width - len;
+        // zero padding goes between sign and digits
+        if self.zero && numeric {
+            return match text.strip_prefix('-') {
+                Some(digits) => format!("-{}{}", "0".repeat(pad), digits),
+                None => format!("{}{}", "0".repeat(pad), text),
+            };
+        }
+        let fill = |n: usize| self.fill.to_string().repeat(n);
+        // numbers are right aligned and text is left aligned by default
+        match self.align.unwrap_or(match numeric {
+            true => Align::Right,
+            false => Align::Left,
+        }) {
+            Align::Left => format!("{}{}", text, fill(pad)),
+            Align::Center => format!("{}{}{}", fill(pad / 2), text, fill(pad - pad / 2)),
+            Align::Right => format!("{}{}", fill(pad), text),
+        }
+    }
+}
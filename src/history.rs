@@ -0,0 +1,75 @@
+use chrono::Duration;
+use std::collections::VecDeque;
+
+/// maximum number of observations within the history
+const CAPACITY: usize = 256;
+
+/// values of a weather update which are remembered
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observation {
+    /// time of the observation, unix, UTC
+    pub dt: i64,
+    /// temperature (in the unit of the weather update)
+    pub temp: f64,
+    /// atmospheric pressure, hPa
+    pub pressure: f64,
+}
+
+impl Observation {
+    /// take the remembered values from a weather update
+    /// #### Parameters
+    /// - `current`: current weather update
+    pub fn new(current: &openweathermap::CurrentWeather) -> Observation {
+        Observation {
+            dt: current.dt,
+            temp: current.main.temp,
+            pressure: current.main.pressure,
+        }
+    }
+}
+
+/// ring buffer of recent observations
+#[derive(Default, Debug)]
+pub struct History {
+    observations: VecDeque<Observation>,
+}
+
+impl History {
+    /// create an empty history
+    pub fn new() -> History {
+        History::default()
+    }
+
+    /// remember an observation (observations of the same time are only remembered once)
+    /// #### Parameters
+    /// - `observation`: observation to remember
+    pub fn push(&mut self, observation: Observation) {
+        if self.observations.back().map(|o| o.dt) == Some(observation.dt) {
+            return;
+        }
+        if self.observations.len() == CAPACITY {
+            self.observations.pop_front();
+        }
+        self.observations.push_back(observation);
+    }
+
+    /// get the change of a value within a period
+    /// #### Parameters
+    /// - `value`: function which gets the value from an observation
+    /// - `period`: period of time to look back
+    /// #### Return value
+    /// - ⇒ `Some(f64)`: change from the oldest observation within the period up to the latest one
+    /// - ⇒ `None`: observations cover less than three quarters of the period
+    pub fn change(&self, value: fn(&Observation) -> f64, period: Duration) -> Option<f64> {
+        let latest = self.observations.back()?;
+        let oldest = self
+            .observations
+            .iter()
+            .find(|o| latest.dt - o.dt <= period.num_seconds())?;
+        let span = latest.dt - oldest.dt;
+        if span * 4 < period.num_seconds() * 3 {
+            return None;
+        }
+        return Some(value(latest) - value(oldest));
+    }
+}
@@ -0,0 +1,128 @@
+use clap::ValueEnum;
+use i3status_ext::io::Io;
+use serde::Serialize;
+use std::fmt;
+
+/// markup of the text within a block
+#[derive(PartialEq, Eq, ValueEnum, Clone, Copy, Debug)]
+pub enum Markup {
+    /// plain text
+    None,
+    /// text may include pango markup like `<span>` or `<b>`
+    Pango,
+}
+
+impl Markup {
+    /// escape text so that it will be displayed as it is
+    /// #### Parameters
+    /// - `text`: text to escape
+    /// #### Return value
+    /// - escaped text
+    pub fn escape(&self, text: &str) -> String {
+        match self {
+            Markup::None => text.to_string(),
+            Markup::Pango => text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('\'', "&#39;")
+                .replace('"', "&quot;"),
+        }
+    }
+}
+
+impl fmt::Display for Markup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Markup::None => "none",
+                Markup::Pango => "pango",
+            }
+        )
+    }
+}
+
+/// i3bar block which will be injected into the json output of i3status like described
+/// [here](https://i3wm.org/docs/i3bar-protocol.html#_blocks_in_detail)
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Block {
+    pub name: String,
+    pub markup: String,
+    pub full_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub urgent: bool,
+}
+
+impl Block {
+    /// create a block without any colors
+    /// #### Parameters
+    /// - `name`: name of the block (could be anything)
+    /// - `markup`: markup of `full_text`
+    /// - `full_text`: text to display
+    pub fn new(name: &str, markup: Markup, full_text: &str) -> Block {
+        Block {
+            name: name.to_string(),
+            markup: markup.to_string(),
+            full_text: full_text.to_string(),
+            short_text: None,
+            color: None,
+            background: None,
+            border: None,
+            urgent: false,
+        }
+    }
+}
+
+/// Insert a block into *i3status*'s json line at given position.
+/// Call this within a loop continuously to add the block into the json data from *i3status*.
+/// #### Parameters
+/// - `io`: input and output channels behind `Io` trait
+/// - `position`: insert block at this position (from left to right)
+/// - `reverse`: reverse `position` to count from right to left.
+/// - `block`: block to insert
+/// #### Return value
+/// - ⇒ `Err(std::io::Error)` of kind `UnexpectedEof` if *i3status* has closed its output
+pub fn update<IO: Io>(
+    io: &mut IO,
+    position: usize,
+    reverse: bool,
+    block: &Block,
+) -> std::io::Result<()> {
+    // read one line from stdin
+    let mut line = io.read_line()?;
+    // an empty line (not even a newline) means that i3status has exited
+    if line.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "i3status has closed its output",
+        ));
+    }
+    // handle prefix comma
+    if line.starts_with(',') {
+        line.remove(0);
+        io.write_line(",")?;
+    }
+    // read all incoming blocks but keep them as they are
+    match serde_json::from_str::<Vec<serde_json::Value>>(&line) {
+        Ok(mut blocks) => {
+            let position = match reverse {
+                true => blocks.len().saturating_sub(1 + position),
+                false => position.min(blocks.len()),
+            };
+            blocks.insert(position, serde_json::to_value(block)?);
+            io.write_line(&format!("{}\n", serde_json::to_string(&blocks)?))?;
+        }
+        _ => io.write_line(&line)?,
+    }
+    Ok(())
+}
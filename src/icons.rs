@@ -0,0 +1,248 @@
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// icon theme
+#[derive(PartialEq, Eq, ValueEnum, Clone, Copy, Debug)]
+pub enum Theme {
+    /// unicode emoji
+    Emoji,
+    /// icons of a Nerd Font
+    Nerd,
+    /// icons of the Weather Icons font
+    WeatherIcons,
+    /// plain ASCII characters
+    Ascii,
+}
+
+/// names of all icons which can be themed
+pub const NAMES: &[&str] = &[
+    "clear_day",
+    "clear_night",
+    "few_clouds",
+    "few_clouds_night",
+    "scattered_clouds",
+    "broken_clouds",
+    "drizzle",
+    "rain",
+    "rain_night",
+    "heavy_rain",
+    "shower_rain",
+    "freezing_rain",
+    "sleet",
+    "snow",
+    "thunderstorm",
+    "thunderstorm_rain",
+    "mist",
+    "dust",
+    "squall",
+    "tornado",
+    "unknown",
+    "wind_n",
+    "wind_ne",
+    "wind_e",
+    "wind_se",
+    "wind_s",
+    "wind_sw",
+    "wind_w",
+    "wind_nw",
+    "iss",
+    "iss_visible",
+    "sunrise",
+    "sunset",
+    "moon_new",
+    "moon_waxing_crescent",
+    "moon_first_quarter",
+    "moon_waxing_gibbous",
+    "moon_full",
+    "moon_waning_gibbous",
+    "moon_last_quarter",
+    "moon_waning_crescent",
+    "alert",
+    "risk_low",
+    "risk_moderate",
+    "risk_high",
+    "risk_very_high",
+    "risk_extreme",
+];
+
+/// icons of all themes in the order of `NAMES`
+const THEMES: &[(Theme, [&str; NAMES.len()])] = &[
+    (
+        Theme::Emoji,
+        [
+            "🌞", "🌛", "🌤", "☁", "⛅", "⛅", "💧", "🌦", "🌧", "☔", "🌧", "🧊", "🌨", "❄", "🌩", "⛈",
+            "🌫", "🏜", "💨", "🌪", "❔", "↓", "↙", "←", "↖", "↑", "↗", "→", "↘", "🛰", "👁", "🌅",
+            "🌇", "🌑", "🌒", "🌓", "🌔", "🌕", "🌖", "🌗", "🌘", "⚠", "🟢", "🟡", "🟠", "🔴",
+            "🟣",
+        ],
+    ),
+    (
+        Theme::Nerd,
+        [
+            "\u{e30d}", "\u{e32b}", "\u{e302}", "\u{e37e}", "\u{e33d}", "\u{e312}", "\u{e31b}",
+            "\u{e308}", "\u{e325}", "\u{e318}", "\u{e319}", "\u{e316}", "\u{e3ad}", "\u{e31a}",
+            "\u{e31d}", "\u{e31c}", "\u{e313}", "\u{e35d}", "\u{e34b}", "\u{e351}", "\u{e374}",
+            "↓", "↙", "←", "↖", "↑", "↗", "→", "↘", "\u{f197}", "\u{f06e}", "\u{e34c}", "\u{e34d}",
+            "\u{e38d}", "\u{e390}", "\u{e394}", "\u{e397}", "\u{e39b}", "\u{e39e}", "\u{e3a2}",
+            "\u{e3a5}", "\u{f071}", "🟢", "🟡", "🟠", "🔴", "🟣",
+        ],
+    ),
+    (
+        Theme::WeatherIcons,
+        [
+            "\u{f00d}", "\u{f02e}", "\u{f002}", "\u{f086}", "\u{f041}", "\u{f013}", "\u{f01c}",
+            "\u{f008}", "\u{f028}", "\u{f019}", "\u{f01a}", "\u{f017}", "\u{f0b5}", "\u{f01b}",
+            "\u{f01e}", "\u{f01d}", "\u{f014}", "\u{f063}", "\u{f050}", "\u{f056}", "\u{f07b}",
+            "\u{f044}", "\u{f043}", "\u{f048}", "\u{f087}", "\u{f058}", "\u{f057}", "\u{f04d}",
+            "\u{f088}", "🛰", "👁", "\u{f051}", "\u{f052}", "\u{f095}", "\u{f098}", "\u{f09c}",
+            "\u{f09f}", "\u{f0a3}", "\u{f0a6}", "\u{f0aa}", "\u{f0ad}", "⚠", "🟢", "🟡", "🟠",
+            "🔴", "🟣",
+        ],
+    ),
+    (
+        Theme::Ascii,
+        [
+            "*", ")", "*~", ")~", "~", "~~", ",", "//", "//", "////", "///", "/#", "/*", "**", "!",
+            "/!", "==", "::", ">>", "@", "?", "v", "/", "<", "\\", "^", "/", ">", "\\", "ISS", "o",
+            "rise", "set", "()", ")", "D", "O)", "O", "(O", "C", "(", "!", "+", "~", "-", "!",
+            "!!",
+        ],
+    ),
+];
+
+/// icon which replaces the one of a theme (given like `name=icon`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Icon {
+    name: String,
+    icon: String,
+}
+
+impl FromStr for Icon {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Icon, String> {
+        let (name, icon) = match s.split_once('=') {
+            Some((name, icon)) => (name.trim(), icon.trim()),
+            None => return Err(format!("missing `=` in icon `{}`", s)),
+        };
+        if !NAMES.contains(&name) {
+            let hint = NAMES
+                .iter()
+                .map(|n| (strsim::jaro_winkler(name, n), n))
+                .filter(|(similarity, _)| *similarity > 0.8)
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, n)| format!(" (did you mean `{}`?)", n))
+                .unwrap_or_default();
+            return Err(format!("unknown icon `{}`{}", name, hint));
+        }
+        Ok(Icon {
+            name: name.to_string(),
+            icon: icon.to_string(),
+        })
+    }
+}
+
+/// icons of a theme including replacements
+#[derive(Clone, Debug)]
+pub struct Icons {
+    icons: HashMap<&'static str, String>,
+}
+
+impl Icons {
+    /// create the icons of a theme
+    /// #### Parameters
+    /// - `theme`: icon theme
+    /// - `replacements`: icons which replace the ones of the theme
+    pub fn new(theme: Theme, replacements: &[Icon]) -> Icons {
+        let (_, icons) = THEMES.iter().find(|(t, _)| *t == theme).unwrap();
+        let mut icons: HashMap<&'static str, String> = NAMES
+            .iter()
+            .zip(icons.iter())
+            .map(|(name, icon)| (*name, icon.to_string()))
+            .collect();
+        for r in replacements {
+            if let Some(icon) = icons.get_mut(r.name.as_str()) {
+                *icon = r.icon.clone();
+            }
+        }
+        return Icons { icons };
+    }
+
+    /// get an icon by its name
+    /// #### Parameters
+    /// - `name`: name of the icon (one of `NAMES`)
+    pub fn get(&self, name: &str) -> &str {
+        self.icons.get(name).map(|i| i.as_str()).unwrap_or("?")
+    }
+
+    /// get the icon which matches an OWM weather condition
+    /// #### Parameters
+    /// - `weather`: weather condition (its icon code tells if it is night like in `01n`)
+    pub fn weather(&self, weather: &openweathermap::Weather) -> &str {
+        let night = weather.icon.ends_with('n');
+        self.get(match (weather.id, night) {
+            (200..=202 | 230..=232, _) => "thunderstorm_rain",
+            (200..=299, _) => "thunderstorm",
+            (300..=399, _) => "drizzle",
+            (502..=504, _) => "heavy_rain",
+            (511, _) => "freezing_rain",
+            (520..=531, _) => "shower_rain",
+            (500..=599, false) => "rain",
+            (500..=599, true) => "rain_night",
+            (611..=616, _) => "sleet",
+            (600..=699, _) => "snow",
+            (711 | 731 | 751 | 761 | 762, _) => "dust",
+            (771, _) => "squall",
+            (781, _) => "tornado",
+            (700..=799, _) => "mist",
+            (800, false) => "clear_day",
+            (800, true) => "clear_night",
+            (801, false) => "few_clouds",
+            (801, true) => "few_clouds_night",
+            (802, _) => "scattered_clouds",
+            (803 | 804, _) => "broken_clouds",
+            _ => "unknown",
+        })
+    }
+
+    /// get the icon of a moon phase
+    /// #### Parameters
+    /// - `index`: one of 8 phases from new moon (`0`) over full moon (`4`) to waning crescent (`7`)
+    pub fn moon(&self, index: usize) -> &str {
+        let names = [
+            "moon_new",
+            "moon_waxing_crescent",
+            "moon_first_quarter",
+            "moon_waxing_gibbous",
+            "moon_full",
+            "moon_waning_gibbous",
+            "moon_last_quarter",
+            "moon_waning_crescent",
+        ];
+        self.get(names[index % 8])
+    }
+
+    /// get the icon of a risk category (like those of air quality or UV index)
+    /// #### Parameters
+    /// - `level`: risk category from low (`1`) to extreme (`5`)
+    pub fn risk(&self, level: usize) -> &str {
+        let names = [
+            "risk_low",
+            "risk_moderate",
+            "risk_high",
+            "risk_very_high",
+            "risk_extreme",
+        ];
+        self.get(names[level.clamp(1, 5) - 1])
+    }
+
+    /// get the arrow icon of a wind direction
+    /// #### Parameters
+    /// - `sector`: one of 8 directions clockwise from north (`0..8`)
+    pub fn wind(&self, sector: usize) -> &str {
+        let names = [
+            "wind_n", "wind_ne", "wind_e", "wind_se", "wind_s", "wind_sw", "wind_w", "wind_nw",
+        ];
+        self.get(names[sector % 8])
+    }
+}
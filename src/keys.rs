@@ -0,0 +1,546 @@
+use crate::property::{Properties, Unit, Value};
+
+/// description of a key which can be used within a format string
+pub struct Key {
+    /// name of the key (without braces)
+    pub name: &'static str,
+    /// what the key displays
+    pub description: &'static str,
+    /// example value
+    pub example: &'static str,
+}
+
+/// all keys which can be used within a format string
+pub const KEYS: &[Key] = &[
+    Key {
+        name: "city",
+        description: "City name",
+        example: "Berlin",
+    },
+    Key {
+        name: "main",
+        description: "Group of weather parameters (Rain, Snow, Extreme etc.)",
+        example: "Clouds",
+    },
+    Key {
+        name: "description",
+        description: "Weather condition within the group",
+        example: "scattered clouds",
+    },
+    Key {
+        name: "conditions",
+        description: "All weather conditions (there may be more than one, e.g. rain with mist)",
+        example: "light rain, mist",
+    },
+    Key {
+        name: "icon",
+        description: "Weather icon",
+        example: "⛅",
+    },
+    Key {
+        name: "pressure",
+        description: "Atmospheric pressure (on the sea level, if there is no sea_level or grnd_level data), hPa",
+        example: "1010",
+    },
+    Key {
+        name: "pressure_sea",
+        description: "Atmospheric pressure on the sea level, hPa (no value if not available)",
+        example: "1015",
+    },
+    Key {
+        name: "pressure_ground",
+        description: "Atmospheric pressure on the ground level, hPa (no value if not available)",
+        example: "1011",
+    },
+    Key {
+        name: "pressure_trend",
+        description: "Pressure tendency within 3 hours as arrow (↑ rising, → steady, ↓ falling; no value until there is enough history)",
+        example: "↓",
+    },
+    Key {
+        name: "humidity",
+        description: "Humidity, %",
+        example: "45",
+    },
+    Key {
+        name: "wind",
+        description: "Wind direction as N, NE, E, SE, S, SW, W or NW (or 16 directions with --compass 16; localized by --lang)",
+        example: "NW",
+    },
+    Key {
+        name: "wind_icon",
+        description: "Wind direction as arrow icon",
+        example: "↘",
+    },
+    Key {
+        name: "wind_speed",
+        description: "Wind speed, {speed_unit}",
+        example: "4",
+    },
+    Key {
+        name: "wind_gust",
+        description: "Wind gust, {speed_unit} (no value if there are no gusts)",
+        example: "7",
+    },
+    Key {
+        name: "beaufort",
+        description: "Wind force on the Beaufort scale (0–12)",
+        example: "3",
+    },
+    Key {
+        name: "beaufort_name",
+        description: "Name of the wind force on the Beaufort scale (in the language of --lang if available, otherwise English)",
+        example: "gentle breeze",
+    },
+    Key {
+        name: "wind_bar",
+        description: "Wind force as bar (▁ for calm up to █ for hurricane)",
+        example: "▂",
+    },
+    Key {
+        name: "wind_deg",
+        description: "Wind direction, degrees (meteorological)",
+        example: "310",
+    },
+    Key {
+        name: "deg_unit",
+        description: "Direction unit (degrees: °)",
+        example: "°",
+    },
+    Key {
+        name: "visibility",
+        description: "Visibility, meter",
+        example: "10000",
+    },
+    Key {
+        name: "visibility_km",
+        description: "Visibility, kilometer",
+        example: "10",
+    },
+    Key {
+        name: "clouds",
+        description: "Cloudiness, %",
+        example: "40",
+    },
+    Key {
+        name: "rain.1h",
+        description: "Rain volume for the last 1 hour, mm (no value if it is not raining)",
+        example: "1.2",
+    },
+    Key {
+        name: "rain.3h",
+        description: "Rain volume for the last 3 hours, mm (no value if it is not raining)",
+        example: "3.4",
+    },
+    Key {
+        name: "rain_in",
+        description: "When rain starts or stops within the next hour (e.g. rain in 12 min, rain stops in 5 min, dry for 1h)",
+        example: "rain in 12 min",
+    },
+    Key {
+        name: "rain_nowcast",
+        description: "Precipitation of the next hour as sparkline (one bar per 5 minutes)",
+        example: "▁▁▁▂▄▃▁▁▁▁▁▁",
+    },
+    Key {
+        name: "snow.1h",
+        description: "Snow volume for the last 1 hour, mm (no value if it is not snowing)",
+        example: "1.1",
+    },
+    Key {
+        name: "snow.3h",
+        description: "Snow volume for the last 3 hours, mm (no value if it is not snowing)",
+        example: "2.4",
+    },
+    Key {
+        name: "temp_min",
+        description: "Minimum temperature at the moment (within large megalopolises and urban areas), {temp_unit}",
+        example: "-8",
+    },
+    Key {
+        name: "temp_max",
+        description: "Maximum temperature at the moment (within large megalopolises and urban areas), {temp_unit}",
+        example: "10",
+    },
+    Key {
+        name: "feels_like",
+        description: "Temperature for the human perception of weather, {temp_unit}",
+        example: "8",
+    },
+    Key {
+        name: "dew_point",
+        description: "Dew point, {temp_unit}",
+        example: "4",
+    },
+    Key {
+        name: "humidex",
+        description: "Humidex (perceived temperature of Environment Canada), {temp_unit}",
+        example: "10",
+    },
+    Key {
+        name: "heat_index",
+        description: "Heat index from 27°C (80°F) on, {temp_unit} (no value if it is colder)",
+        example: "29",
+    },
+    Key {
+        name: "wind_chill",
+        description: "Wind chill up to 10°C (50°F) and from 4.8 km/h (3 mi/h) wind speed on, {temp_unit} (no value otherwise)",
+        example: "-3",
+    },
+    Key {
+        name: "temp",
+        description: "Temperature, {temp_unit}",
+        example: "11",
+    },
+    Key {
+        name: "temp_trend",
+        description: "Temperature trend within the last hour as arrow (↗ warmer, → steady, ↘ colder; no value until there is enough history)",
+        example: "↗",
+    },
+    Key {
+        name: "temp_delta_1h",
+        description: "Temperature change within the last hour, {temp_unit} (no value until there is enough history)",
+        example: "1.5",
+    },
+    Key {
+        name: "temp_unit",
+        description: "Temperature unit (standard=K, metric=°C, imperial=°F)",
+        example: "°C",
+    },
+    Key {
+        name: "speed_unit",
+        description: "Wind speed unit (standard=m/s, metric=m/s, imperial=mi/h)",
+        example: "m/s",
+    },
+    Key {
+        name: "update",
+        description: "Local time of last update, HH:MM",
+        example: "12:45",
+    },
+    Key {
+        name: "sunrise",
+        description: "Local time of sunrise at the location, HH:MM",
+        example: "06:12",
+    },
+    Key {
+        name: "sunset",
+        description: "Local time of sunset at the location, HH:MM",
+        example: "20:35",
+    },
+    Key {
+        name: "daylight",
+        description: "Length of the day (from sunrise to sunset), h:mm",
+        example: "14:23",
+    },
+    Key {
+        name: "daylight_left",
+        description: "Remaining daylight until sunset, h:mm (no value at night)",
+        example: "3:05",
+    },
+    Key {
+        name: "sun_next",
+        description: "Icon of the next sunrise (🌅) or sunset (🌇) and the time until it",
+        example: "🌇 in 1:24",
+    },
+    Key {
+        name: "moon_icon",
+        description: "Moon phase as icon (mirrored on the southern hemisphere)",
+        example: "🌔",
+    },
+    Key {
+        name: "moon_phase",
+        description: "Moon phase (new moon, waxing crescent, first quarter, waxing gibbous, full moon, waning gibbous, last quarter or waning crescent)",
+        example: "waxing gibbous",
+    },
+    Key {
+        name: "moon_illumination",
+        description: "Illuminated fraction of the moon, %",
+        example: "78",
+    },
+    Key {
+        name: "moonrise",
+        description: "Local time of moonrise at the location, HH:MM (no value if the moon does not rise today)",
+        example: "15:48",
+    },
+    Key {
+        name: "moonset",
+        description: "Local time of moonset at the location, HH:MM (no value if the moon does not set today)",
+        example: "02:31",
+    },
+    Key {
+        name: "iss",
+        description: "ISS spotting time (HH:MM) or latency (-hh:mm:ss) or duration (+hh:mm:ss)",
+        example: "-12:34",
+    },
+    Key {
+        name: "iss_icon",
+        description: "Satellite icon if ISS is visible",
+        example: "🛰",
+    },
+    Key {
+        name: "iss_space",
+        description: "Space (' ') if any ISS information is displayed",
+        example: " ",
+    },
+    Key {
+        name: "forecast.<n>h.time",
+        description: "Time of the forecast in <n> hours (n = 3, 6, …, 120), HH:MM",
+        example: "15:00",
+    },
+    Key {
+        name: "forecast.<n>h.temp",
+        description: "Forecasted temperature in <n> hours, {temp_unit}",
+        example: "13",
+    },
+    Key {
+        name: "forecast.<n>h.feels_like",
+        description: "Forecasted temperature for the human perception of weather in <n> hours, {temp_unit}",
+        example: "12",
+    },
+    Key {
+        name: "forecast.<n>h.icon",
+        description: "Forecasted weather icon in <n> hours",
+        example: "🌧",
+    },
+    Key {
+        name: "forecast.<n>h.description",
+        description: "Forecasted weather condition in <n> hours",
+        example: "light rain",
+    },
+    Key {
+        name: "forecast.<n>h.humidity",
+        description: "Forecasted humidity in <n> hours, %",
+        example: "70",
+    },
+    Key {
+        name: "forecast.<n>h.wind_speed",
+        description: "Forecasted wind speed in <n> hours, {speed_unit}",
+        example: "5",
+    },
+    Key {
+        name: "forecast.<n>h.pop",
+        description: "Probability of precipitation in <n> hours, %",
+        example: "40",
+    },
+    Key {
+        name: "forecast.<n>h.rain",
+        description: "Forecasted rain volume for the 3 hours before <n> hours, mm (no value if it will not rain)",
+        example: "0.6",
+    },
+    Key {
+        name: "forecast.today.temp_min",
+        description: "Lowest temperature of the rest of today (current temperature and forecast), {temp_unit}",
+        example: "6",
+    },
+    Key {
+        name: "forecast.today.temp_max",
+        description: "Highest temperature of the rest of today (current temperature and forecast), {temp_unit}",
+        example: "14",
+    },
+    Key {
+        name: "forecast.today.icon",
+        description: "Forecasted weather icon of today (around noon)",
+        example: "⛅",
+    },
+    Key {
+        name: "forecast.today.description",
+        description: "Forecasted weather condition of today (around noon)",
+        example: "scattered clouds",
+    },
+    Key {
+        name: "forecast.tomorrow.temp_min",
+        description: "Forecasted minimum temperature of tomorrow, {temp_unit}",
+        example: "7",
+    },
+    Key {
+        name: "forecast.tomorrow.temp_max",
+        description: "Forecasted maximum temperature of tomorrow, {temp_unit}",
+        example: "16",
+    },
+    Key {
+        name: "forecast.tomorrow.icon",
+        description: "Forecasted weather icon of tomorrow (around noon)",
+        example: "🌤",
+    },
+    Key {
+        name: "forecast.tomorrow.description",
+        description: "Forecasted weather condition of tomorrow (around noon)",
+        example: "few clouds",
+    },
+    Key {
+        name: "forecast_strip",
+        description: "Compact forecast of the next days (see --forecast-days) with weekday, icon and lowest/highest temperature",
+        example: "Mo🌧9/14 Tu🌞11/19 We⛅10/16",
+    },
+    Key {
+        name: "aqi",
+        description: "Air Quality Index (1 = Good, 2 = Fair, 3 = Moderate, 4 = Poor, 5 = Very Poor)",
+        example: "2",
+    },
+    Key {
+        name: "aqi_icon",
+        description: "Air Quality Index as colored icon (🟢, 🟡, 🟠, 🔴, 🟣)",
+        example: "🟡",
+    },
+    Key {
+        name: "pm2_5",
+        description: "Concentration of fine particles matter (PM2.5), µg/m³",
+        example: "8",
+    },
+    Key {
+        name: "pm10",
+        description: "Concentration of coarse particulate matter (PM10), µg/m³",
+        example: "12",
+    },
+    Key {
+        name: "o3",
+        description: "Concentration of ozone (O3), µg/m³",
+        example: "68",
+    },
+    Key {
+        name: "no2",
+        description: "Concentration of nitrogen dioxide (NO2), µg/m³",
+        example: "15",
+    },
+    Key {
+        name: "uv",
+        description: "UV index",
+        example: "4",
+    },
+    Key {
+        name: "uv_level",
+        description: "UV risk category (low, moderate, high, very high or extreme)",
+        example: "moderate",
+    },
+    Key {
+        name: "uv_icon",
+        description: "UV risk category as colored icon (🟢, 🟡, 🟠, 🔴, 🟣)",
+        example: "🟡",
+    },
+    Key {
+        name: "alert",
+        description: "Event name of the first active weather alert (no value if there is none)",
+        example: "Wind warning",
+    },
+    Key {
+        name: "alert_count",
+        description: "Number of active weather alerts",
+        example: "1",
+    },
+    Key {
+        name: "alert_icon",
+        description: "Warning icon if there is any active weather alert",
+        example: "⚠",
+    },
+];
+
+/// placeholder within key names for the hours of a forecast (3, 6, …, 120)
+pub const HOURS: &str = "<n>";
+
+/// all values the placeholder `<n>` can take
+pub fn hours() -> impl Iterator<Item = u32> {
+    (3..=120).step_by(3)
+}
+
+impl Key {
+    /// check if a name matches this key (including placeholders)
+    /// #### Parameters
+    /// - `name`: name of the key (without braces)
+    pub fn matches(&self, name: &str) -> bool {
+        match self.name.split_once(HOURS) {
+            Some((prefix, suffix)) => name
+                .strip_prefix(prefix)
+                .and_then(|n| n.strip_suffix(suffix))
+                .and_then(|n| n.parse::<u32>().ok())
+                .is_some_and(|n| hours().any(|h| h == n)),
+            None => self.name == name,
+        }
+    }
+
+    /// get all names this key matches (replaces placeholders by their possible values)
+    pub fn names(&self) -> Vec<String> {
+        match self.name.contains(HOURS) {
+            true => hours()
+                .map(|n| self.name.replace(HOURS, &n.to_string()))
+                .collect(),
+            false => vec![self.name.to_string()],
+        }
+    }
+}
+
+/// find a key by its name
+/// #### Parameters
+/// - `name`: name of the key (without braces)
+pub fn find(name: &str) -> Option<&'static Key> {
+    KEYS.iter().find(|k| k.matches(name))
+}
+
+/// check if a key is known
+/// #### Parameters
+/// - `name`: name of the key (without braces)
+/// #### Return value
+/// - `true` if key can be used in a format string
+pub fn is_known(name: &str) -> bool {
+    find(name).is_some()
+}
+
+/// get a hint which names the most similar key
+/// #### Parameters
+/// - `name`: misspelled name of a key (without braces)
+/// #### Return value
+/// - hint like ` (did you mean `{temp}`?)` or empty string if there is no similar key
+pub fn hint(name: &str) -> String {
+    match similar(name) {
+        Some(similar) => format!(" (did you mean `{{{}}}`?)", similar),
+        None => String::new(),
+    }
+}
+
+/// get properties which contain the example values of all keys
+pub fn examples() -> Properties {
+    let mut props = Properties::new();
+    for key in KEYS {
+        for name in key.names() {
+            props.insert(
+                &name,
+                match key.example.parse::<f64>() {
+                    Ok(n) => Value::number(n, Unit::None, None),
+                    Err(_) => Value::text(key.example),
+                },
+            );
+        }
+    }
+    return props;
+}
+
+/// list all keys with their description and example value
+/// #### Return value
+/// - one line per key
+pub fn list() -> String {
+    let width = KEYS.iter().map(|k| k.name.len()).max().unwrap_or(0) + 2;
+    KEYS.iter()
+        .map(|k| {
+            format!(
+                "{:width$} {} (e.g. `{}`)\n",
+                format!("{{{}}}", k.name),
+                k.description,
+                k.example,
+                width = width
+            )
+        })
+        .collect()
+}
+
+/// find the known key which is most similar to a misspelled one
+/// #### Parameters
+/// - `name`: misspelled name of a key (without braces)
+/// #### Return value
+/// - ⇒ `Some(&str)`: name of the most similar key
+/// - ⇒ `None`: no key is similar enough
+pub fn similar(name: &str) -> Option<&'static str> {
+    KEYS.iter()
+        .map(|k| (strsim::jaro_winkler(name, k.name), k.name))
+        .filter(|(similarity, _)| *similarity > 0.8)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, name)| name)
+}
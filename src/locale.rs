@@ -0,0 +1,190 @@
+use clap::ValueEnum;
+
+/// names of the Beaufort scale (0–12) in several languages
+const BEAUFORT: &[(&str, [&str; 13])] = &[
+    (
+        "en",
+        [
+            "calm",
+            "light air",
+            "light breeze",
+            "gentle breeze",
+            "moderate breeze",
+            "fresh breeze",
+            "strong breeze",
+            "near gale",
+            "gale",
+            "strong gale",
+            "storm",
+            "violent storm",
+            "hurricane",
+        ],
+    ),
+    (
+        "de",
+        [
+            "Windstille",
+            "leiser Zug",
+            "leichte Brise",
+            "schwache Brise",
+            "mäßige Brise",
+            "frische Brise",
+            "starker Wind",
+            "steifer Wind",
+            "stürmischer Wind",
+            "Sturm",
+            "schwerer Sturm",
+            "orkanartiger Sturm",
+            "Orkan",
+        ],
+    ),
+    (
+        "fr",
+        [
+            "calme",
+            "très légère brise",
+            "légère brise",
+            "petite brise",
+            "jolie brise",
+            "bonne brise",
+            "vent frais",
+            "grand frais",
+            "coup de vent",
+            "fort coup de vent",
+            "tempête",
+            "violente tempête",
+            "ouragan",
+        ],
+    ),
+    (
+        "es",
+        [
+            "calma",
+            "ventolina",
+            "flojito",
+            "flojo",
+            "bonancible",
+            "fresquito",
+            "fresco",
+            "frescachón",
+            "temporal",
+            "temporal fuerte",
+            "temporal duro",
+            "temporal muy duro",
+            "temporal huracanado",
+        ],
+    ),
+    (
+        "it",
+        [
+            "calma",
+            "bava di vento",
+            "brezza leggera",
+            "brezza tesa",
+            "vento moderato",
+            "vento teso",
+            "vento fresco",
+            "vento forte",
+            "burrasca",
+            "burrasca forte",
+            "tempesta",
+            "fortunale",
+            "uragano",
+        ],
+    ),
+];
+
+/// 16 wind directions (clockwise from north) in several languages
+const DIRECTIONS: &[(&str, [&str; 16])] = &[
+    (
+        "en",
+        [
+            "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+            "NW", "NNW",
+        ],
+    ),
+    (
+        "de",
+        [
+            "N", "NNO", "NO", "ONO", "O", "OSO", "SO", "SSO", "S", "SSW", "SW", "WSW", "W", "WNW",
+            "NW", "NNW",
+        ],
+    ),
+    (
+        "fr",
+        [
+            "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSO", "SO", "OSO", "O", "ONO",
+            "NO", "NNO",
+        ],
+    ),
+    (
+        "es",
+        [
+            "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSO", "SO", "OSO", "O", "ONO",
+            "NO", "NNO",
+        ],
+    ),
+    (
+        "it",
+        [
+            "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSO", "SO", "OSO", "O", "ONO",
+            "NO", "NNO",
+        ],
+    ),
+];
+
+/// number of points of a compass
+#[derive(PartialEq, Eq, ValueEnum, Clone, Copy, Debug)]
+pub enum Compass {
+    /// 8 directions like N, NE, E
+    #[clap(name = "8")]
+    Eight,
+    /// 16 directions like N, NNE, NE
+    #[clap(name = "16")]
+    Sixteen,
+}
+
+impl Compass {
+    /// get the sector of the compass a direction points to
+    /// #### Parameters
+    /// - `deg`: direction in degrees (meteorological)
+    /// #### Return value
+    /// - index of the nearest direction clockwise from north (`0..8` or `0..16`)
+    pub fn sector(&self, deg: f64) -> usize {
+        let points = match self {
+            Compass::Eight => 8,
+            Compass::Sixteen => 16,
+        };
+        // round to the nearest sector so that e.g. 350° is north
+        return (deg.rem_euclid(360.0) * points as f64 / 360.0).round() as usize % points;
+    }
+
+    /// get the name of a direction
+    /// #### Parameters
+    /// - `lang`: two character language code (falls back to English if unknown)
+    /// - `deg`: direction in degrees (meteorological)
+    pub fn name(&self, lang: &str, deg: f64) -> &'static str {
+        let names = DIRECTIONS
+            .iter()
+            .find(|(l, _)| *l == lang)
+            .unwrap_or(&DIRECTIONS[0])
+            .1;
+        return match self {
+            Compass::Eight => names[self.sector(deg) * 2],
+            Compass::Sixteen => names[self.sector(deg)],
+        };
+    }
+}
+
+/// get the name of a Beaufort number
+/// #### Parameters
+/// - `lang`: two character language code (falls back to English if unknown)
+/// - `beaufort`: Beaufort number (0–12)
+pub fn beaufort_name(lang: &str, beaufort: usize) -> &'static str {
+    let names = BEAUFORT
+        .iter()
+        .find(|(l, _)| *l == lang)
+        .unwrap_or(&BEAUFORT[0])
+        .1;
+    return names[beaufort.min(12)];
+}
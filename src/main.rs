@@ -1,3 +1,6 @@
+// the code base prefers explicit `return` statements and `match` expressions
+#![allow(clippy::needless_return, clippy::single_match, clippy::upper_case_acronyms)]
+
 extern crate chrono;
 extern crate i3status_ext;
 extern crate notify_rust;
@@ -6,17 +9,38 @@ extern crate openweathermap;
 extern crate clap;
 
 use clap::Parser;
-use std::collections::HashMap;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+mod air;
+mod config;
+mod fetch;
+mod forecast;
+mod format;
+mod history;
+mod i3bar;
+mod icons;
+mod keys;
 mod level;
+mod locale;
+mod moon;
 mod notify;
+mod onecall;
+mod property;
+mod rule;
 mod spot;
 mod weather;
 
+use format::Format;
+use history::{History, Observation};
+use i3bar::{Block, Markup};
+use icons::{Icon, Icons, Theme};
 use level::Level;
+use locale::Compass;
 use notify::Notify;
+use property::Properties;
+use rule::Rule;
+use std::path::PathBuf;
 use spot::*;
 use weather::*;
 
@@ -24,8 +48,14 @@ use weather::*;
 mod tests;
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
+#[clap(author, version, about, long_about = None, args_override_self = true)]
 struct Args {
+    /// Configuration file (default: ~/.config/i3owm/config.toml)
+    ///
+    /// Every entry `name = value` in the TOML file sets the option `--name`. Arrays repeat the option and booleans set flags. Options given at the command line override those from the configuration file while repeatable options are appended.
+    #[clap(long, value_parser)]
+    config: Option<PathBuf>,
+
     /// Location city name, city ID or coordinate
     ///
     /// City's name maybe followed by comma-separated 2-letter (state code for the USA locations and) country code (ISO3166) or city ID (see https://openweathermap.org/find) or geographical coordinate as comma-separated latitude and longitude.
@@ -33,69 +63,35 @@ struct Args {
     location: String,
 
     /// OpenWeatherMap API key (see at https://openweathermap.org/api)
-    #[clap(short = 'k', long, value_parser)]
-    apikey: String,
+    #[clap(
+        short = 'k',
+        long,
+        value_parser,
+        required_unless_present_any = &["list-keys", "check-format"]
+    )]
+    apikey: Option<String>,
 
     /// Display format string
     ///
-    /// Format string including one ore more of the following keys
-    ///
-    ///   {city}          City name
-    ///
-    ///   {main}          Group of weather parameters (Rain, Snow, Extreme etc.)
-    ///
-    ///   {description}   Weather condition within the group
-    ///
-    ///   {icon}          Weather icon
-    ///
-    ///   {pressure}      Atmospheric pressure (on the sea level, if there is no sea_level or grnd_level data), hPa
-    ///
-    ///   {humidity}      Humidity, %
-    ///
-    ///   {wind}          Wind direction as N, NW, W, SW, S, SO, O or NO
-    ///
-    ///   {wind_icon}     Wind direction as arrow icon
-    ///
-    ///   {wind_speed}    Wind speed, {speed_unit}
-    ///
-    ///   {wind_deg}      Wind direction, degrees (meteorological)
-    ///
-    ///   {deg_unit}      Direction unit (degrees: °)
-    ///
-    ///   {visibility}    Visibility, meter
-    ///
-    ///   {visibility_km} Visibility, kilometer
-    ///
-    ///   {rain.1h}       Rain volume for the last 1 hour, mm
-    ///
-    ///   {rain.3h}       Rain volume for the last 3 hours, mm
-    ///
-    ///   {snow.1h}       Snow volume for the last 1 hour, mm
-    ///
-    ///   {snow.3h}       Snow volume for the last 3 hours, mm
+    /// Format string including one ore more keys like {city}, {icon} or {temp} (see --list-keys for all available keys).
     ///
-    ///   {temp_min}      Minimum temperature at the moment. This is minimal currently observed temperature (within large megalopolises and urban areas), {temp_unit}
+    /// Sections like {?rain.1h: ☔{rain.1h}mm} will only be displayed if the key behind '?' has a value.
     ///
-    ///   {temp_max}      Maximum temperature at the moment. This is maximal currently observed temperature (within large megalopolises and urban areas), {temp_unit}
+    /// Literal braces have to be written as {{ and }}.
     ///
-    ///   {feels_like}    Temperature. This temperature parameter accounts for the human perception of weather, {temp_unit}
+    /// Keys may be followed by a format specifier like {temp:.1}, {wind_speed:>3} or {humidity:02}.
     ///
-    ///   {temp}          Temperature, {temp_unit}
-    ///
-    ///   {temp_unit}     Temperature (standard=K, metric=°C, imperial=°F)
-    ///
-    ///   {speed_unit}    Wind speed unit
-    ///                   (standard=m/s, metric=m/s, imperial=mi/h)
-    ///
-    ///   {update}        Local time of last update, HH:MM
-    ///
-    ///   {iss}           ISS spotting time (HH:MM) or latency (-hh::mm::ss) or duration (+hh::mm::ss)
-    ///
-    ///   {iss_icon}      show 🛰  if ISS is visible
-    ///
-    ///   {iss_space}     space (' ') if any ISS information is displayed
-    #[clap(short, long, value_parser, default_value_t=String::from("{city} {icon} {temp}{temp_unit}"))]
-    format: String,
+    /// If this option is given multiple times the formats will be displayed one after another (see --rotate).
+    #[clap(short, long, value_parser, default_value = "{city} {icon} {temp}{temp_unit}")]
+    format: Vec<Format>,
+
+    /// Duration in seconds each format is displayed if multiple formats are given (0 = do not rotate)
+    #[clap(short = 'R', long, value_parser, default_value_t = 10)]
+    rotate: u64,
+
+    /// Short display format string which i3bar shows if there is not enough space (e.g. '{icon}{temp}')
+    #[clap(long, value_parser)]
+    short_format: Option<Format>,
 
     /// Position of output in JSON when wrapping i3status
     #[clap(short, long, value_parser, default_value_t = 0)]
@@ -105,6 +101,18 @@ struct Args {
     #[clap(short, long, value_parser, default_value_t = String::from("en"))]
     lang: String,
 
+    /// Number of points of the compass which {wind} displays (8 = N, NE, E, … or 16 = N, NNE, NE, …)
+    #[clap(long, value_enum, default_value_t = Compass::Eight)]
+    compass: Compass,
+
+    /// Icon theme (weather-icons and nerd need the matching font)
+    #[clap(long, value_enum, default_value_t = Theme::Emoji)]
+    icon_theme: Theme,
+
+    /// Replace an icon of the theme like 'clear_day=☀' (may be given multiple times, see README for all names)
+    #[clap(long, value_parser)]
+    icon: Vec<Icon>,
+
     /// Reverse position (from right)
     #[clap(short, long, action)]
     reverse: bool,
@@ -117,6 +125,30 @@ struct Args {
     #[clap(short = 'P', long, value_parser, default_value_t = 10)]
     poll: u64,
 
+    /// Duration of polling period of the forecast in minutes (only used if any `{forecast...}` key is displayed)
+    #[clap(long, value_parser, default_value_t = 60)]
+    forecast_poll: u64,
+
+    /// Number of days displayed by `{forecast_strip}` (including today)
+    #[clap(long, value_parser, default_value_t = 3)]
+    forecast_days: usize,
+
+    /// Duration of polling period of the air pollution in minutes (only used if any air quality key is displayed)
+    #[clap(long, value_parser, default_value_t = 60)]
+    air_poll: u64,
+
+    /// Air Quality Index (1 = Good … 5 = Very Poor) from which on the block gets urgent (0 = never)
+    #[clap(long, value_parser, default_value_t = 4)]
+    aqi_urgent: u8,
+
+    /// Duration of polling period of the One Call API in minutes (only used if any UV, alert or nowcast key is displayed or --rain-notify is given)
+    #[clap(long, value_parser, default_value_t = 30)]
+    onecall_poll: u64,
+
+    /// Show a notification this number of minutes before rain starts (needs the One Call API)
+    #[clap(long, value_parser)]
+    rain_notify: Option<i64>,
+
     /// Duration in minutes when ISS rising is "soon" in minutes
     #[clap(short, long, value_parser, default_value_t = 15)]
     soon: i64,
@@ -142,7 +174,7 @@ struct Args {
     #[clap(short, long, action)]
     blink: bool,
 
-    /// Show notifications about ISS getting visible
+    /// Show notifications about ISS getting visible and new weather alerts
     #[clap(short, long, action)]
     notify: bool,
 
@@ -150,31 +182,114 @@ struct Args {
     #[clap(short, long, action)]
     test: bool,
 
+    /// List all keys which can be used within format strings and exit
+    #[clap(long, action)]
+    list_keys: bool,
+
+    /// Check all format strings, show them with example values and exit
+    #[clap(long, action)]
+    check_format: bool,
+
     /// Number of ISS spottings that will be fetched from open-notify.org
     #[clap(short = 'T', long, value_parser, default_value_t = 100)]
     prevision: u8,
+
+    /// Text color rule like 'temp < 0 -> #88c0ff' (first matching rule wins)
+    ///
+    /// A rule compares a key with a threshold by one of the operators <, <=, >, >=, == or != and selects the color behind '->' if it matches. Text can only be compared by == and !=.
+    #[clap(long, value_parser)]
+    color: Vec<Rule>,
+
+    /// Background color rule like 'wind_speed > 15 -> #ff5555' (first matching rule wins)
+    #[clap(long, value_parser)]
+    background: Vec<Rule>,
+
+    /// Border color rule like 'temp > 30 -> #ff0000' (first matching rule wins)
+    #[clap(long, value_parser)]
+    border: Vec<Rule>,
+
+    /// Markup of the format string
+    ///
+    /// none = show format string as it is
+    /// pango = format string may include pango markup like <span size='small'>{description}</span> or <b>{temp}</b> (values will be escaped)
+    #[clap(short = 'm', long, value_enum, default_value_t = Markup::None)]
+    markup: Markup,
+}
+impl Args {
+    /// get the names of all keys used within any format
+    fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.format.iter().flat_map(|f| f.keys()).collect();
+        if let Some(ref short_format) = self.short_format {
+            keys.extend(short_format.keys());
+        }
+        return keys;
+    }
+
+    /// get the format which shall be displayed at the moment
+    /// #### Parameters
+    /// - `elapsed`: time since program start
+    fn current_format(&self, elapsed: Duration) -> &Format {
+        let index = match self.rotate {
+            0 => 0,
+            rotate => (elapsed.as_secs() / rotate) as usize % self.format.len(),
+        };
+        return &self.format[index];
+    }
 }
+
 /// continuously inject weather into incoming json lines from i3status and pass through
 fn main() {
-    // fetch arguments
-    let args = Args::parse();
+    // fetch arguments (including those from the configuration file)
+    let args = match config::args(std::env::args().collect()) {
+        Ok(args) => Args::parse_from(args),
+        Err(e) => clap::Error::raw(clap::ErrorKind::Io, format!("{}\n", e)).exit(),
+    };
+    // list keys or check formats (which already have been parsed) without fetching anything
+    if args.list_keys {
+        print!("{}", keys::list());
+        return;
+    }
+    if args.check_format {
+        let examples = keys::examples();
+        let formats = args.format.iter().chain(args.short_format.iter());
+        for format in formats {
+            println!("{} → {}", format, format.render(&examples, Markup::None));
+        }
+        return;
+    }
     // start our observatory via OWM
     let owm = &openweathermap::init(
         &args.location,
         &args.units,
         &args.lang,
-        &args.apikey,
+        args.apikey.as_ref().unwrap(),
         args.poll,
     );
     // open-notify receiver will get created if we get coordinates from weather update
     let mut iss: Option<open_notify::Receiver> = None;
+    // forecast receiver will get created if we get coordinates from weather update
+    let mut fc: Option<forecast::Receiver> = None;
+    // latest weather update
+    let mut current: Option<openweathermap::CurrentWeather> = None;
+    // recent weather updates to get trends from
+    let mut history = History::new();
+    // latest One Call API update
+    let mut onecall: Option<onecall::OneCall> = None;
+    // latest forecast update
+    let mut forecast: Option<forecast::Forecast> = None;
+    // air pollution receiver will get created if we get coordinates from weather update
+    let mut ap: Option<air::Receiver> = None;
+    // latest Air Quality Index (0 = unknown)
+    let mut aqi: u8 = 0;
+    // One Call API receiver will get created if we get coordinates from weather update
+    let mut oc: Option<onecall::Receiver> = None;
     // start i3status parsing
     let mut io = match args.test {
         false => i3status_ext::begin().unwrap(),
         true => i3status_ext::begin_dummy().unwrap(),
     };
-    // we may override format for error messages
-    let mut format_str = openweathermap::LOADING.to_string();
+    // we may show an error message instead of the formatted output
+    let mut message: Option<String> = Some(openweathermap::LOADING.to_string());
     // remember visibility from weather report for ISS spotting
     let mut visible: bool = false;
     // remember daytime from weather report for ISS spotting
@@ -183,13 +298,17 @@ fn main() {
     // remember duration of current spotting event in milliseconds for motification timeout
     let mut duration = Duration::from_millis(0);
     // state of current notification
-    let mut notify = Notify::new(args.notify);
+    let mut notify = Notify::new(!args.notify);
     // create blinking flag
     let mut blinking: bool = false;
     // latest spotting update
     let mut spottings: Vec<open_notify::Spot> = Vec::new();
+    // remember program start to rotate formats
+    let started = Instant::now();
+    // icons of the selected theme
+    let icons = Icons::new(args.icon_theme, &args.icon);
     // all fetched information
-    let mut props: HashMap<&str, String> = new_properties();
+    let mut props: Properties = new_properties();
     loop {
         // update current weather info if there is an update available
         match openweathermap::update(owm) {
@@ -203,7 +322,7 @@ fn main() {
                         dt = Some(&daytime);
                     }
                     // check if we have to start open_notify thread
-                    if iss.is_none() && args.format.contains("{iss_") {
+                    if iss.is_none() && args.keys().iter().any(|k| k.starts_with("iss")) {
                         iss = Some(open_notify::init(
                             w.coord.lat,
                             w.coord.lon,
@@ -212,12 +331,67 @@ fn main() {
                             90,
                         ));
                     }
+                    // check if we have to start forecast thread
+                    if fc.is_none() && args.keys().iter().any(|k| k.starts_with("forecast")) {
+                        fc = Some(forecast::init(
+                            w.coord.lat,
+                            w.coord.lon,
+                            &args.units,
+                            &args.lang,
+                            args.apikey.as_ref().unwrap(),
+                            args.forecast_poll,
+                        ));
+                    }
+                    // check if we have to start air pollution thread
+                    let air_keys = ["aqi", "pm2_5", "pm10", "o3", "no2"];
+                    if ap.is_none()
+                        && args
+                            .keys()
+                            .iter()
+                            .any(|k| air_keys.iter().any(|a| k.starts_with(a)))
+                    {
+                        ap = Some(air::init(
+                            w.coord.lat,
+                            w.coord.lon,
+                            args.apikey.as_ref().unwrap(),
+                            args.air_poll,
+                        ));
+                    }
+                    // check if we have to start One Call API thread
+                    let onecall_keys = ["uv", "alert", "rain_in", "rain_nowcast"];
+                    if oc.is_none()
+                        && (args.rain_notify.is_some()
+                            || args
+                                .keys()
+                                .iter()
+                                .any(|k| onecall_keys.iter().any(|o| k.starts_with(o))))
+                    {
+                        oc = Some(onecall::init(
+                            w.coord.lat,
+                            w.coord.lon,
+                            &args.lang,
+                            args.apikey.as_ref().unwrap(),
+                            args.onecall_poll,
+                        ));
+                    }
                     // get weather properties
-                    get_weather(&mut props, &w, &args.units);
-                    // reset format string
-                    format_str = args.format.to_string();
+                    get_weather(
+                        &mut props,
+                        &w,
+                        &args.units,
+                        &args.lang,
+                        args.compass,
+                        &icons,
+                    );
+                    // remember weather to get trends
+                    history.push(Observation::new(&w));
+                    get_trends(&mut props, &history, &args.units);
+                    // remember weather for continuous updates
+                    current = Some(w);
+                    // reset error message
+                    message = None;
                 }
-                Err(e) => format_str = e,
+                Err(e) => message = Some(e),
             },
             None => (),
         }
@@ -232,13 +406,13 @@ fn main() {
                         };
                         // rememeber current spotting events
                         spottings = s;
-                        // reset format string
-                        format_str = args.format.to_string();
+                        // reset error message
+                        message = None;
                     }
                     Err(e) => {
                         // do not show "loading..." twice
                         if e != openweathermap::LOADING {
-                            format_str = e
+                            message = Some(e)
                         }
                     }
                 },
@@ -246,6 +420,46 @@ fn main() {
             },
             None => (),
         }
+        // errors of additional sources do not hide the current weather
+        if let Some(f) = fetch::latest(&fc, "forecast") {
+            // remember forecast
+            forecast = Some(f);
+        }
+        if let Some(a) = fetch::latest(&ap, "air pollution") {
+            // get air pollution properties
+            aqi = get_air(&mut props, &a, &icons);
+        }
+        if let Some(o) = fetch::latest(&oc, "One Call API") {
+            // get One Call API properties
+            get_onecall(&mut props, &o, &icons);
+            // announce new weather alerts
+            notify.alerts(&o.alerts);
+            // remember nowcast
+            onecall = Some(o);
+        }
+        // continuously get sun and moon properties because they are relative to now
+        if let Some(ref w) = current {
+            get_sun(&mut props, w, &icons, chrono::Utc::now());
+            get_moon(&mut props, w, &icons, chrono::Utc::now());
+        }
+        // continuously get nowcast properties because they are relative to now
+        if let Some(ref o) = onecall {
+            let rain = get_nowcast(&mut props, o, chrono::Utc::now());
+            if let Some(before) = args.rain_notify {
+                notify.rain(rain, before);
+            }
+        }
+        // continuously get forecast properties because they are relative to now
+        if let Some(ref f) = forecast {
+            get_forecast(
+                &mut props,
+                f,
+                &args.units,
+                args.forecast_days,
+                &icons,
+                chrono::Utc::now(),
+            );
+        }
         // continuously get spot properties
         let level = get_spots(
             &mut props,
@@ -255,6 +469,7 @@ fn main() {
             dt,
             blinking,
             &args.level,
+            &icons,
         );
         // check if we shall generate a notification
         notify.notification(duration, level);
@@ -262,41 +477,37 @@ fn main() {
         if args.blink {
             blinking = !blinking;
         }
-        let output = format_string(&format_str, &props);
+        let block = match message {
+            Some(ref m) => Block::new("i3owm", args.markup, &args.markup.escape(m)),
+            None => Block {
+                color: rule::color(&args.color, &props),
+                background: rule::color(&args.background, &props),
+                border: rule::color(&args.border, &props),
+                urgent: args.aqi_urgent > 0 && aqi >= args.aqi_urgent,
+                short_text: args
+                    .short_format
+                    .as_ref()
+                    .map(|f| f.render(&props, args.markup)),
+                ..Block::new(
+                    "i3owm",
+                    args.markup,
+                    &args
+                        .current_format(started.elapsed())
+                        .render(&props, args.markup),
+                )
+            },
+        };
         if !args.test {
-            // insert current properties and print json string or original line
-            i3status_ext::update(&mut io, "i3owm", args.position, args.reverse, &output).unwrap();
+            // insert current block and print json string or original line
+            match i3bar::update(&mut io, args.position, args.reverse, &block) {
+                Ok(()) => (),
+                // i3status has exited
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return,
+                Err(e) => clap::Error::raw(clap::ErrorKind::Io, format!("{}\n", e)).exit(),
+            }
         } else {
-            println!("{}", output);
+            println!("{}", block.full_text);
             thread::sleep(Duration::from_secs(1));
         }
     }
 }
-
-/// insert properties into format string
-/// #### Parameters
-/// - `format`: output format (string including some of the available keys)
-/// - `props`: property map to get data to insert from
-/// #### Return value
-/// - formatted string
-fn format_string(format: &str, props: &HashMap<&str, String>) -> String {
-    let mut result: String = format.to_string();
-    let mut iss: bool = false;
-    // replace all keys by their values
-    for (k, v) in props {
-        let r = result.replace(k, v);
-        if r != result {
-            result = r;
-            // tests if an '{iss_' key of value was inserted
-            iss = iss || (k.contains("{iss_") && v != "");
-        }
-    }
-    // insert space at '{iss_space}' if we inserted '{iss_' keys of value
-    return result.replace(
-        "{iss_space}",
-        match iss {
-            true => " ",
-            false => "",
-        },
-    );
-}
@@ -0,0 +1,172 @@
+use chrono::{DateTime, Duration, Utc};
+use std::f64::consts::PI;
+
+// Low precision formulas of the moon's position and phase after
+// [suncalc](https://github.com/mourner/suncalc) which are accurate enough to
+// display phase, moonrise and moonset within minutes.
+
+/// radians per degree
+const RAD: f64 = PI / 180.0;
+/// obliquity of the earth
+const E: f64 = RAD * 23.4397;
+/// distance between sun and earth, km
+const SUN_DISTANCE: f64 = 149_598_000.0;
+
+/// position of a celestial body in equatorial coordinates
+struct Equatorial {
+    /// right ascension, radians
+    ra: f64,
+    /// declination, radians
+    dec: f64,
+    /// distance to earth, km
+    dist: f64,
+}
+
+/// days since J2000.0
+fn days(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 86_400_000.0 - 0.5 + 2_440_588.0 - 2_451_545.0
+}
+
+/// convert ecliptic longitude and latitude into equatorial coordinates
+fn equatorial(l: f64, b: f64, dist: f64) -> Equatorial {
+    Equatorial {
+        ra: (l.sin() * E.cos() - b.tan() * E.sin()).atan2(l.cos()),
+        dec: (b.sin() * E.cos() + b.cos() * E.sin() * l.sin()).asin(),
+        dist,
+    }
+}
+
+/// position of the sun
+fn sun(d: f64) -> Equatorial {
+    let m = RAD * (357.5291 + 0.98560028 * d);
+    let c = RAD * (1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin());
+    let l = m + c + RAD * 102.9372 + PI;
+    equatorial(l, 0.0, SUN_DISTANCE)
+}
+
+/// position of the moon
+fn moon(d: f64) -> Equatorial {
+    let l = RAD * (218.316 + 13.176396 * d);
+    let m = RAD * (134.963 + 13.064993 * d);
+    let f = RAD * (93.272 + 13.229350 * d);
+    equatorial(
+        l + RAD * 6.289 * m.sin(),
+        RAD * 5.128 * f.sin(),
+        385_001.0 - 20_905.0 * m.cos(),
+    )
+}
+
+/// get the moon's phase and illuminated fraction
+/// #### Parameters
+/// - `time`: point in time
+/// #### Return value
+/// - phase (`0` = new moon, `0.25` = first quarter, `0.5` = full moon, `0.75` = last quarter)
+///   and illuminated fraction (`0`…`1`)
+pub fn phase(time: DateTime<Utc>) -> (f64, f64) {
+    let d = days(time);
+    let s = sun(d);
+    let m = moon(d);
+    let phi = (s.dec.sin() * m.dec.sin() + s.dec.cos() * m.dec.cos() * (s.ra - m.ra).cos()).acos();
+    let inc = (s.dist * phi.sin()).atan2(m.dist - s.dist * phi.cos());
+    let angle = (s.dec.cos() * (s.ra - m.ra).sin())
+        .atan2(s.dec.sin() * m.dec.cos() - s.dec.cos() * m.dec.sin() * (s.ra - m.ra).cos());
+    let sign = match angle < 0.0 {
+        true => -1.0,
+        false => 1.0,
+    };
+    return (0.5 + 0.5 * inc * sign / PI, (1.0 + inc.cos()) / 2.0);
+}
+
+/// get the moon's altitude above the horizon (including refraction)
+/// #### Parameters
+/// - `time`: point in time
+/// - `latitude`: geo location, latitude
+/// - `longitude`: geo location, longitude
+/// #### Return value
+/// - altitude in degrees
+pub fn altitude(time: DateTime<Utc>, latitude: f64, longitude: f64) -> f64 {
+    let d = days(time);
+    let m = moon(d);
+    let phi = RAD * latitude;
+    // local hour angle from sidereal time
+    let h = RAD * (280.16 + 360.9856235 * d) + RAD * longitude - m.ra;
+    let alt = (phi.sin() * m.dec.sin() + phi.cos() * m.dec.cos() * h.cos()).asin();
+    let refraction = alt.max(0.0);
+    let refraction = 0.0002967 / (refraction + 0.00312536 / (refraction + 0.08901179)).tan();
+    return (alt + refraction) / RAD;
+}
+
+/// get moonrise and moonset within a day
+/// #### Parameters
+/// - `start`: start of the day (local midnight)
+/// - `latitude`: geo location, latitude
+/// - `longitude`: geo location, longitude
+/// #### Return value
+/// - moonrise and moonset (each `None` if the moon does not rise or set within that day)
+pub fn rise_set(
+    start: DateTime<Utc>,
+    latitude: f64,
+    longitude: f64,
+) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    // altitude of the moon's center at rise and set
+    let hc = 0.133;
+    let alt = |hours: f64| {
+        altitude(
+            start + Duration::seconds((hours * 3600.0) as i64),
+            latitude,
+            longitude,
+        ) - hc
+    };
+    let time = |hours: f64| Some(start + Duration::seconds((hours * 3600.0) as i64));
+    let mut rise = None;
+    let mut set = None;
+    // search crossings of the horizon by quadratic interpolation within steps of two hours
+    let mut h0 = alt(0.0);
+    for i in (1..24).step_by(2) {
+        let i = i as f64;
+        let h1 = alt(i);
+        let h2 = alt(i + 1.0);
+        let a = (h0 + h2) / 2.0 - h1;
+        let b = (h2 - h0) / 2.0;
+        let xe = -b / (2.0 * a);
+        let ye = (a * xe + b) * xe + h1;
+        let d = b * b - 4.0 * a * h1;
+        if d >= 0.0 {
+            let dx = d.sqrt() / (a.abs() * 2.0);
+            let mut x1 = xe - dx;
+            let x2 = xe + dx;
+            let mut roots = 0;
+            if x1.abs() <= 1.0 {
+                roots += 1;
+            }
+            if x2.abs() <= 1.0 {
+                roots += 1;
+            }
+            if x1 < -1.0 {
+                x1 = x2;
+            }
+            match roots {
+                1 => match h0 < 0.0 {
+                    true => rise = time(i + x1),
+                    false => set = time(i + x1),
+                },
+                2 => match ye < 0.0 {
+                    true => {
+                        rise = time(i + x2);
+                        set = time(i + x1);
+                    }
+                    false => {
+                        rise = time(i + x1);
+                        set = time(i + x2);
+                    }
+                },
+                _ => (),
+            }
+        }
+        if rise.is_some() && set.is_some() {
+            break;
+        }
+        h0 = h2;
+    }
+    return (rise, set);
+}
@@ -1,12 +1,16 @@
+use std::collections::HashSet;
 use std::time::Duration;
 use notify_rust::{Notification, Urgency};
 use crate::level::Level;
+use crate::onecall::Alert;
 
 /// state of the current notification to prevent multiple notifications appear at the same time
 pub struct Notify {
     suppress: bool,
     soon: bool,
     visible: bool,
+    announced: HashSet<(String, String, i64)>,
+    rain: bool,
 }
 
 impl Notify {
@@ -17,9 +21,11 @@ impl Notify {
     /// returns the state instance
     pub fn new(suppress: bool) -> Notify {
         Notify {
-            suppress: suppress,
+            suppress,
             soon: true,
             visible: true,
+            announced: HashSet::new(),
+            rain: true,
         }
     }
     /// notify user about soon or current spotting ISS
@@ -59,4 +65,73 @@ impl Notify {
             }
         }
     }
+
+    /// notify user about weather alerts which have not been announced yet
+    /// #### Parameters
+    /// - `alerts`: currently active weather alerts
+    pub fn alerts(&mut self, alerts: &[Alert]) {
+        for alert in self.unannounced(alerts) {
+            if !self.suppress {
+                Notification::new()
+                    .appname("i3owm")
+                    .summary(&format!("{}: {}", alert.sender_name, alert.event))
+                    .body(&alert.description)
+                    .urgency(Urgency::Critical)
+                    .show()
+                    .unwrap();
+            }
+        }
+    }
+
+    /// remember weather alerts as announced
+    /// #### Parameters
+    /// - `alerts`: currently active weather alerts
+    /// #### Return value
+    /// - alerts which have not been announced before
+    pub fn unannounced<'a>(&mut self, alerts: &'a [Alert]) -> Vec<&'a Alert> {
+        alerts
+            .iter()
+            .filter(|a| {
+                self.announced
+                    .insert((a.sender_name.clone(), a.event.clone(), a.start))
+            })
+            .collect()
+    }
+
+    /// notify user about upcoming rain (independent from `suppress`)
+    /// #### Parameters
+    /// - `minutes`: minutes until rain starts (`None` if there is no rain within the nowcast)
+    /// - `before`: minutes before rain starts when to notify
+    pub fn rain(&mut self, minutes: Option<i64>, before: i64) {
+        if self.rain_due(minutes, before) {
+            Notification::new()
+                .appname("i3owm")
+                .summary("Upcoming: Rain")
+                .body(&format!("Rain will start in {} min!", minutes.unwrap_or(0)))
+                .urgency(Urgency::Normal)
+                .show()
+                .unwrap();
+        }
+    }
+
+    /// check if upcoming rain shall be announced (once until it gets dry again)
+    /// #### Parameters
+    /// - `minutes`: minutes until rain starts (`None` if there is no rain within the nowcast)
+    /// - `before`: minutes before rain starts when to notify
+    pub fn rain_due(&mut self, minutes: Option<i64>, before: i64) -> bool {
+        match minutes {
+            // rain is about to start
+            Some(m) if m > 0 && m <= before => {
+                let due = self.rain;
+                self.rain = false;
+                return due;
+            }
+            // no announcement when it is already raining
+            Some(0) => self.rain = false,
+            // dry again
+            None => self.rain = true,
+            _ => (),
+        }
+        return false;
+    }
 }
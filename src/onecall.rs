@@ -0,0 +1,69 @@
+use serde::Deserialize;
+
+use crate::fetch;
+
+/// current weather data of the One Call API
+#[derive(Deserialize, Debug)]
+pub struct Current {
+    /// Current UV index
+    pub uvi: f64,
+}
+
+/// national weather alert
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Alert {
+    /// Name of the alert source
+    pub sender_name: String,
+    /// Alert event name
+    pub event: String,
+    /// Date and time of the start of the alert, Unix, UTC
+    pub start: i64,
+    /// Description of the alert
+    pub description: String,
+}
+
+/// minute forecast of precipitation
+#[derive(Deserialize, Debug)]
+pub struct Minutely {
+    /// Time of the forecasted data, unix, UTC
+    pub dt: i64,
+    /// Precipitation, mm/h
+    pub precipitation: f64,
+}
+
+/// weather data like described [here](https://openweathermap.org/api/one-call-3)
+#[derive(Deserialize, Debug)]
+pub struct OneCall {
+    /// current weather data
+    pub current: Option<Current>,
+    /// precipitation forecast of the next hour in steps of one minute (missing if not available)
+    #[serde(default)]
+    pub minutely: Vec<Minutely>,
+    /// national weather alerts (missing if there are none)
+    #[serde(default)]
+    pub alerts: Vec<Alert>,
+}
+
+/// Receiver object you get from `init()` and have to handle to `fetch::update()`.
+pub type Receiver = fetch::Receiver<OneCall>;
+
+/// Spawns a thread which fetches One Call API data periodically.
+/// #### Parameters
+/// - `latitude`: geo location, latitude
+/// - `longitude`: geo location, longitude
+/// - `lang`: language code of weather descriptions
+/// - `api_key`: OpenWeatherMap API key (must be subscribed to the One Call API 3.0)
+/// - `poll_mins`: duration of poll period in minutes
+pub fn init(latitude: f64, longitude: f64, lang: &str, api_key: &str, poll_mins: u64) -> Receiver {
+    fetch::init(
+        format!(
+            "{}/3.0/onecall?lat={}&lon={}&exclude=hourly,daily&lang={}&appid={}",
+            fetch::OWM_URL,
+            latitude,
+            longitude,
+            lang,
+            api_key
+        ),
+        poll_mins,
+    )
+}
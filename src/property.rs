@@ -0,0 +1,242 @@
+use chrono::{DateTime, Duration, FixedOffset};
+use std::collections::HashMap;
+use std::fmt;
+
+/// physical unit of a number
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Unit {
+    /// plain number
+    None,
+    /// percentage, %
+    Percent,
+    /// angle, °
+    Degree,
+    /// pressure, hPa
+    HectoPascal,
+    /// length, m
+    Meter,
+    /// length, km
+    Kilometer,
+    /// volume of precipitation, mm
+    Millimeter,
+    /// temperature, K
+    Kelvin,
+    /// temperature, °C
+    Celsius,
+    /// temperature, °F
+    Fahrenheit,
+    /// speed, m/s
+    MeterPerSecond,
+    /// speed, mi/h
+    MilePerHour,
+    /// concentration, µg/m³
+    MicrogramPerCubicMeter,
+}
+
+impl Unit {
+    /// get temperature unit of an OpenWeatherMap unit system
+    /// #### Parameters
+    /// - `units`: either `"standard"`, `"metric"` or `"imperial"`
+    pub fn temperature(units: &str) -> Unit {
+        match units {
+            "standard" => Unit::Kelvin,
+            "metric" => Unit::Celsius,
+            "imperial" => Unit::Fahrenheit,
+            _ => Unit::None,
+        }
+    }
+
+    /// get speed unit of an OpenWeatherMap unit system
+    /// #### Parameters
+    /// - `units`: either `"standard"`, `"metric"` or `"imperial"`
+    pub fn speed(units: &str) -> Unit {
+        match units {
+            "standard" | "metric" => Unit::MeterPerSecond,
+            "imperial" => Unit::MilePerHour,
+            _ => Unit::None,
+        }
+    }
+
+    /// convert a temperature of this unit into degrees Celsius
+    /// #### Parameters
+    /// - `value`: temperature in this unit
+    pub fn in_celsius(&self, value: f64) -> f64 {
+        match self {
+            Unit::Kelvin => value - 273.15,
+            Unit::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+            _ => value,
+        }
+    }
+
+    /// convert a temperature in degrees Celsius into this unit
+    /// #### Parameters
+    /// - `celsius`: temperature in degrees Celsius
+    pub fn of_celsius(&self, celsius: f64) -> f64 {
+        match self {
+            Unit::Kelvin => celsius + 273.15,
+            Unit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+            _ => celsius,
+        }
+    }
+
+    /// convert a speed of this unit into kilometers per hour
+    /// #### Parameters
+    /// - `value`: speed in this unit
+    pub fn in_kilometer_per_hour(&self, value: f64) -> f64 {
+        match self {
+            Unit::MeterPerSecond => value * 3.6,
+            Unit::MilePerHour => value * 1.609344,
+            _ => value,
+        }
+    }
+
+    /// symbol which is displayed for this unit
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::None => "",
+            Unit::Percent => "%",
+            Unit::Degree => "°",
+            Unit::HectoPascal => "hPa",
+            Unit::Meter => "m",
+            Unit::Kilometer => "km",
+            Unit::Millimeter => "mm",
+            Unit::Kelvin => "K",
+            Unit::Celsius => "°C",
+            Unit::Fahrenheit => "°F",
+            Unit::MeterPerSecond => "m/s",
+            Unit::MilePerHour => "mi/h",
+            Unit::MicrogramPerCubicMeter => "µg/m³",
+        }
+    }
+}
+
+/// value of a property which will be formatted at render time
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// number with unit and the precision it is displayed by default (shortest representation if `None`)
+    Number {
+        value: f64,
+        unit: Unit,
+        precision: Option<usize>,
+    },
+    /// signed duration which is displayed like `+01:15` or `-02:21`
+    Duration(Duration),
+    /// unsigned length of time which is displayed like `1:24` (hours and minutes)
+    Span(Duration),
+    /// point in time which is displayed like `12:45`
+    Time(DateTime<FixedOffset>),
+    /// any text
+    Text(String),
+    /// unicode symbol
+    Icon(String),
+}
+
+impl Value {
+    /// create a number value
+    pub fn number(value: f64, unit: Unit, precision: Option<usize>) -> Value {
+        Value::Number {
+            value,
+            unit,
+            precision,
+        }
+    }
+
+    /// create a text value
+    pub fn text(text: &str) -> Value {
+        Value::Text(text.to_string())
+    }
+
+    /// create an icon value
+    pub fn icon(icon: &str) -> Value {
+        Value::Icon(icon.to_string())
+    }
+
+    /// check if value would not display anything
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Value::Text(t) | Value::Icon(t) => t.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number {
+                value,
+                precision: Some(precision),
+                ..
+            } => write!(f, "{:.*}", precision, value),
+            Value::Number { value, .. } => write!(f, "{}", value),
+            Value::Duration(d) => {
+                let sign = match *d < Duration::zero() {
+                    true => '-',
+                    false => '+',
+                };
+                let d = d.abs();
+                // leave out leading hours and minutes if they are zero
+                let hours = d.num_hours();
+                let minutes = d.num_minutes() % 60;
+                let seconds = d.num_seconds() % 60;
+                if hours > 0 {
+                    write!(f, "{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds)
+                } else if minutes > 0 {
+                    write!(f, "{}{:02}:{:02}", sign, minutes, seconds)
+                } else {
+                    write!(f, "{}{:02}", sign, seconds)
+                }
+            }
+            Value::Span(d) => write!(f, "{}:{:02}", d.num_hours(), d.num_minutes() % 60),
+            Value::Time(t) => write!(f, "{}", t.format("%H:%M")),
+            Value::Text(t) | Value::Icon(t) => write!(f, "{}", t),
+        }
+    }
+}
+
+/// map of all properties which can be inserted into a format
+#[derive(Clone, Debug, Default)]
+pub struct Properties {
+    values: HashMap<String, Value>,
+}
+
+impl Properties {
+    /// create empty property map
+    pub fn new() -> Properties {
+        Properties::default()
+    }
+
+    /// insert or replace the value of a key
+    pub fn insert(&mut self, key: &str, value: Value) {
+        self.values.insert(key.to_string(), value);
+    }
+
+    /// insert the unit symbol of another number property
+    /// #### Parameters
+    /// - `key`: key to insert the unit symbol into
+    /// - `of`: key of the number which's unit shall be inserted
+    pub fn insert_unit(&mut self, key: &str, of: &str) {
+        if let Some(Value::Number { unit, .. }) = self.values.get(of) {
+            let symbol = unit.symbol();
+            self.insert(key, Value::text(symbol));
+        }
+    }
+
+    /// remove the value of a key
+    ///
+    /// Remove keys whose data is missing in an update instead of inserting an empty value.
+    /// They will then be rendered as empty text and sections including them will be skipped.
+    pub fn remove(&mut self, key: &str) {
+        self.values.remove(key);
+    }
+
+    /// get the value of a key
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// check if a key has a value which would display anything
+    pub fn has_value(&self, key: &str) -> bool {
+        self.get(key).is_some_and(|v| !v.is_empty())
+    }
+}
@@ -0,0 +1,131 @@
+use regex::Regex;
+use std::str::FromStr;
+
+use crate::keys;
+use crate::property::{Properties, Value};
+
+/// comparison operator of a rule
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Op {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+}
+
+/// value a property is compared with
+#[derive(Clone, Debug, PartialEq)]
+pub enum Threshold {
+    Number(f64),
+    Text(String),
+}
+
+/// rule like `temp < 0 -> #88c0ff` which selects a color if a property matches a threshold
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    key: String,
+    op: Op,
+    threshold: Threshold,
+    color: String,
+}
+
+impl Rule {
+    /// parse a rule
+    /// #### Parameters
+    /// - `rule`: rule string `<key> <op> <threshold> -> <color>` with operators `<`, `<=`, `>`, `>=`, `==` or `!=`
+    /// #### Return value
+    /// - ⇒ `Ok(Rule)`: parsed rule
+    /// - ⇒ `Err(String)`: error message if rule could not be parsed
+    pub fn parse(rule: &str) -> Result<Rule, String> {
+        let re = Regex::new(r"^\s*([^\s<>=!]+)\s*(<=|>=|==|!=|<|>)\s*(.*?)\s*(->|→)\s*(\S+)\s*$")
+            .unwrap();
+        let caps = match re.captures(rule) {
+            Some(caps) => caps,
+            None => return Err(format!("invalid rule `{}`", rule)),
+        };
+        let key = caps[1].to_string();
+        if !keys::is_known(&key) {
+            return Err(format!(
+                "unknown key `{{{}}}` in rule `{}`{}",
+                key,
+                rule,
+                keys::hint(&key)
+            ));
+        }
+        let op = match &caps[2] {
+            "<" => Op::Less,
+            "<=" => Op::LessEqual,
+            ">" => Op::Greater,
+            ">=" => Op::GreaterEqual,
+            "==" => Op::Equal,
+            _ => Op::NotEqual,
+        };
+        let threshold = match caps[3].parse::<f64>() {
+            Ok(n) => Threshold::Number(n),
+            Err(_) => match op {
+                Op::Equal | Op::NotEqual => Threshold::Text(caps[3].to_string()),
+                _ => return Err(format!("threshold must be a number in rule `{}`", rule)),
+            },
+        };
+        let color = caps[5].to_string();
+        let hex = Regex::new(r"^#([0-9a-fA-F]{6}|[0-9a-fA-F]{8})$").unwrap();
+        if !hex.is_match(&color) {
+            return Err(format!("invalid color `{}` in rule `{}`", color, rule));
+        }
+        Ok(Rule {
+            key,
+            op,
+            threshold,
+            color,
+        })
+    }
+
+    /// check if the rule matches the current properties
+    /// #### Parameters
+    /// - `props`: property map to get the value to compare from
+    pub fn matches(&self, props: &Properties) -> bool {
+        let value = match props.get(&self.key) {
+            Some(value) => value,
+            None => return false,
+        };
+        match (value, &self.threshold) {
+            (Value::Number { value, .. }, Threshold::Number(threshold)) => match self.op {
+                Op::Less => value < threshold,
+                Op::LessEqual => value <= threshold,
+                Op::Greater => value > threshold,
+                Op::GreaterEqual => value >= threshold,
+                Op::Equal => value == threshold,
+                Op::NotEqual => value != threshold,
+            },
+            (value, Threshold::Text(threshold)) => match self.op {
+                Op::Equal => value.to_string() == *threshold,
+                Op::NotEqual => value.to_string() != *threshold,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+    fn from_str(rule: &str) -> Result<Rule, String> {
+        Rule::parse(rule)
+    }
+}
+
+/// get the color of the first matching rule
+/// #### Parameters
+/// - `rules`: rules to check in order
+/// - `props`: property map to get the values to compare from
+/// #### Return value
+/// - ⇒ `Some(String)`: color of the first matching rule
+/// - ⇒ `None`: no rule matches
+pub fn color(rules: &[Rule], props: &Properties) -> Option<String> {
+    rules
+        .iter()
+        .find(|r| r.matches(props))
+        .map(|r| r.color.clone())
+}
@@ -1,10 +1,11 @@
 use chrono::prelude::*;
-use std::collections::HashMap;
 pub use open_notify::DayTime;
+use crate::icons::{Icons, Theme};
 use crate::level::Level;
+use crate::property::{Properties, Value};
 
-pub fn new_properties<'a>() -> HashMap<&'a str, String> {
-    let mut props: HashMap<&str, String> = HashMap::new();
+pub fn new_properties() -> Properties {
+    let mut props = Properties::new();
     // insert empty values to all spotting properties (so that we can replace them when ISS report is still missing)
     get_spots(
         &mut props,
@@ -14,6 +15,7 @@ pub fn new_properties<'a>() -> HashMap<&'a str, String> {
         None,
         false,
         &Level::RISE,
+        &Icons::new(Theme::Emoji, &[]),
     );
     return props;
 }
@@ -27,21 +29,24 @@ pub fn new_properties<'a>() -> HashMap<&'a str, String> {
 /// - `daytime`: some daytime if spotting at daytime should be skipped
 /// - `blink`: `true` if icon shall blink while spotting
 /// - `level`: maximum level of spotting display that is wanted
+/// - `icons`: icons of the selected theme
 /// #### Return value
 /// - level of spotting display that was used
+#[allow(clippy::too_many_arguments)]
 pub fn get_spots(
-    props: &mut HashMap<&str, String>,
+    props: &mut Properties,
     spots: &Vec<open_notify::Spot>,
     soon_mins: i64,
     visible: bool,
     daytime: Option<&DayTime>,
     blink: bool,
     level: &Level,
+    icons: &Icons,
 ) -> Level {
     // some icons
-    let satellite = "🛰".to_string();
-    let eye = "👁".to_string();
-    let empty = "".to_string();
+    let satellite = Value::icon(icons.get("iss"));
+    let eye = Value::icon(icons.get("iss_visible"));
+    let empty = Value::text("");
     // get current and upcoming spotting event
     let current = open_notify::find_current(spots, daytime, chrono::Local::now());
     let upcoming = open_notify::find_upcoming(spots, daytime, chrono::Local::now());
@@ -50,28 +55,16 @@ pub fn get_spots(
         match current {
             // check if we have a current spotting event
             Some(spot) => {
-                // insert space property
-                props.insert("{iss_space}", " ".to_string());
                 // insert (maybe blinking) icon
                 props.insert(
-                    "{iss_icon}",
+                    "iss_icon",
                     match blink {
                         false => satellite.clone(),
                         true => eye.clone(),
                     },
                 );
-                // calculate duration until current spotting event
-                let duration = Local::now() - spot.risetime;
-                // format duration (remove any leading zeros)
-                let duration = format!(
-                    "+{:02}:{:02}:{:02}",
-                    duration.num_hours(),
-                    duration.num_minutes() % 60,
-                    duration.num_seconds() % 60
-                )
-                .replace("00:", "");
-                // insert duration
-                props.insert("{iss}", duration);
+                // insert duration since current spotting event has begun
+                props.insert("iss", Value::Duration(Local::now() - spot.risetime));
                 return Level::WATCH;
             }
             // if not check if we have an upcoming spotting event
@@ -81,35 +74,29 @@ pub fn get_spots(
                     let duration = spot.risetime - Local::now();
                     // check if duration is soon
                     if duration < chrono::Duration::minutes(soon_mins)
-                        && [Level::SOON, Level::RISE, Level::FAR].contains(&level)
+                        && [Level::SOON, Level::RISE, Level::FAR].contains(level)
                     {
-                        // insert space property
-                        props.insert("{iss_space}", " ".to_string());
                         // insert icon
-                        props.insert("{iss_icon}", satellite.clone());
-                        // format duration (remove any leading zeros)
-                        let duration = format!(
-                            "-{:02}:{:02}:{:02}",
-                            duration.num_hours(),
-                            duration.num_minutes() % 60,
-                            duration.num_seconds() % 60
-                        )
-                        .replace("00:", "");
-                        // insert duration
-                        props.insert("{iss}", duration);
+                        props.insert("iss_icon", satellite.clone());
+                        // insert (negative) duration until upcoming spotting event
+                        props.insert("iss", Value::Duration(-duration));
                         return Level::SOON;
-                    } else if [Level::RISE, Level::FAR].contains(&level) {
-                        // insert space property
-                        props.insert("{iss_space}", " ".to_string());
+                    } else if [Level::RISE, Level::FAR].contains(level) {
                         // insert icon
-                        props.insert("{iss_icon}", satellite.clone());
+                        props.insert("iss_icon", satellite.clone());
                         // format and insert time
                         if duration > chrono::Duration::days(2) {
-                            props.insert("{iss}", format!("{}d", duration.num_days()));
+                            props.insert(
+                                "iss",
+                                Value::Text(format!("{}d", duration.num_days())),
+                            );
                         } else if duration > chrono::Duration::days(1) {
-                            props.insert("{iss}", format!("{}h", duration.num_hours()));
+                            props.insert(
+                                "iss",
+                                Value::Text(format!("{}h", duration.num_hours())),
+                            );
                         } else {
-                            props.insert("{iss}", spot.risetime.format("%R").to_string());
+                            props.insert("iss", Value::Time(spot.risetime.into()));
                         }
                         return Level::RISE;
                     }
@@ -118,13 +105,14 @@ pub fn get_spots(
                     if level == &Level::FAR {
                         match spots.last() {
                             Some(spot) => {
-                                // insert space property
-                                props.insert("{iss_space}", " ".to_string());
                                 let duration = spot.risetime - Local::now();
                                 // insert icon
-                                props.insert("{iss_icon}", satellite.clone());
+                                props.insert("iss_icon", satellite.clone());
                                 // format and insert time
-                                props.insert("{iss}", format!(">{}", duration.num_days()));
+                                props.insert(
+                                    "iss",
+                                    Value::Text(format!(">{}", duration.num_days())),
+                                );
                                 return Level::FAR;
                             }
                             _ => ()
@@ -135,7 +123,7 @@ pub fn get_spots(
         }
     }
     // remove unused keys
-    props.insert("{iss_icon}", empty.clone());
-    props.insert("{iss}", empty.clone());
+    props.insert("iss_icon", empty.clone());
+    props.insert("iss", empty.clone());
     return Level::NONE;
 }
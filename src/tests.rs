@@ -1,5 +1,7 @@
 // Note this useful idiom: importing names from outer (for mod tests) scope.
 use super::*;
+use chrono::TimeZone;
+use property::{Unit, Value};
 use regex::Regex;
 
 fn apikey() -> String {
@@ -12,48 +14,53 @@ fn apikey() -> String {
     }
 }
 
+fn emoji() -> Icons {
+    Icons::new(Theme::Emoji, &[])
+}
+
+fn condition(id: u64, icon: &str) -> openweathermap::Weather {
+    openweathermap::Weather {
+        id,
+        main: String::new(),
+        description: String::new(),
+        icon: icon.to_string(),
+    }
+}
+
 fn test_key(format: &str, level: &Level, n: u8) -> String {
     match openweathermap::blocking::weather("Berlin,DE", "metric", "en", &apikey()) {
         Ok(w) => {
-            let mut props: HashMap<&str, String> = HashMap::new();
-            get_weather(&mut props, &w, &"metric");
+            let mut props = Properties::new();
+            get_weather(&mut props, &w, "metric", "en", Compass::Eight, &emoji());
             match open_notify::blocking::spot(w.coord.lat, w.coord.lon, 0.0, n) {
                 Ok(spots) => {
-                    get_spots(&mut props, &spots, 30, true, None, false, &level);
-                    let s = format_string(format, &props);
+                    get_spots(&mut props, &spots, 30, true, None, false, level, &emoji());
+                    let s = Format::parse(format).unwrap().render(&props, Markup::None);
                     // check if all keys have been replaced
                     assert!(s.find("{").is_none());
                     assert!(s.find("}").is_none());
                     return s;
                 }
-                Err(_e) => {
-                    assert!(false);
-                    return String::new();
-                }
+                Err(e) => panic!("{}", e),
             }
         }
-        Err(_e) => {
-            assert!(false);
-            return String::new();
-        }
+        Err(e) => panic!("{}", e),
     }
 }
 
 #[test]
 fn test_allkeys() {
-    // take long help to have a sample with all keys in it
-    let yaml = load_yaml!("arg.yaml");
-    let mut format = Vec::new();
-    App::from(yaml).write_long_help(&mut format).unwrap();
-    let format: String = std::str::from_utf8(format.as_slice()).unwrap().to_string();
-    // cut out the example at the end because it contains `{` and `}` which are not marking any key names
-    let format: &str = &format[0..format.find("EXAMPLE:").unwrap()];
+    // build a sample with all keys in it
+    let format: String = keys::KEYS
+        .iter()
+        .map(|k| format!("{{{}}} ", k.name.replace(keys::HOURS, "3")))
+        .collect();
     test_key(&format, &Level::RISE, 100);
 }
 
 #[test]
 fn test_keydoublette() {
-    test_key(&"{update}{update}", &Level::RISE, 100);
+    test_key("{update}{update}", &Level::RISE, 100);
 }
 
 #[test]
@@ -62,12 +69,999 @@ fn test_iss_levels() {
     let rise = Regex::new(r"🛰(\d?\d\.\d?\d\.\d\d\d\d)?(\d?\d:)?\d?\d").unwrap();
     let soon = Regex::new(r"🛰-((\d\d:)?\d\d:)?\d\d").unwrap();
     let watch = Regex::new(r"[🛰👁]\+((\d?\d:)?\d?\d:)?\d\d").unwrap();
-    let s = test_key(&"{iss_icon}{iss}{iss_space}", &Level::FAR, 100);
+    let s = test_key("{iss_icon}{iss}{iss_space}", &Level::FAR, 100);
     assert!(far.is_match(&s) || rise.is_match(&s) || soon.is_match(&s) || watch.is_match(&s));
-    let s = test_key(&"{iss_icon}{iss}{iss_space}", &Level::RISE, 100);
+    let s = test_key("{iss_icon}{iss}{iss_space}", &Level::RISE, 100);
     assert!(s.is_empty() || rise.is_match(&s) || soon.is_match(&s) || watch.is_match(&s));
-    let s = test_key(&"{iss_icon}{iss}{iss_space}", &Level::SOON, 100);
+    let s = test_key("{iss_icon}{iss}{iss_space}", &Level::SOON, 100);
     assert!(s.is_empty() || soon.is_match(&s) || watch.is_match(&s));
-    let s = test_key(&"{iss_icon}{iss}{iss_space}", &Level::WATCH, 100);
+    let s = test_key("{iss_icon}{iss}{iss_space}", &Level::WATCH, 100);
     assert!(s.is_empty() || watch.is_match(&s));
 }
+
+#[test]
+fn test_format_parse() {
+    let format = Format::parse("{city} {icon} {temp}{temp_unit}").unwrap();
+    assert_eq!(format.keys(), vec!["city", "icon", "temp", "temp_unit"]);
+    assert_eq!(
+        Format::parse("{city} {temprature}").unwrap_err(),
+        "unknown key `{temprature}` at column 8 (did you mean `{temp}`?)"
+    );
+    assert_eq!(
+        Format::parse("{city} {temp").unwrap_err(),
+        "unclosed key at column 8"
+    );
+    assert_eq!(
+        Format::parse("{?rain.1h: {rain.1h}mm").unwrap_err(),
+        "unclosed section at column 1"
+    );
+    assert_eq!(
+        Format::parse("{?rain.1h}").unwrap_err(),
+        "missing `:` in section at column 1"
+    );
+}
+
+#[test]
+fn test_format_render() {
+    let mut props = new_properties();
+    props.insert("temp", Value::number(12.3, Unit::Celsius, Some(0)));
+    props.insert_unit("temp_unit", "temp");
+    // overlapping keys must not clobber each other
+    let format = Format::parse("{temp}{temp_unit} {temp_unit}").unwrap();
+    assert_eq!(format.render(&props, Markup::None), "12°C °C");
+    // '{iss_space}' is only inserted if any ISS information is displayed
+    let format = Format::parse("{iss_icon}{iss}{iss_space}{temp}").unwrap();
+    assert_eq!(format.render(&props, Markup::None), "12");
+    props.insert("iss_icon", Value::icon("🛰"));
+    props.insert("iss", Value::text("12:10"));
+    assert_eq!(format.render(&props, Markup::None), "🛰12:10 12");
+    // ...and it stands for 'iss' within sections too
+    let format = Format::parse("{?iss_space:ISS }{temp}").unwrap();
+    assert_eq!(format.keys(), vec!["iss", "temp"]);
+    assert_eq!(format.render(&props, Markup::None), "ISS 12");
+    props.remove("iss");
+    assert_eq!(format.render(&props, Markup::None), "12");
+    assert_eq!(
+        Format::parse("{iss_space:>4}").unwrap_err(),
+        "`{iss_space}` takes no format specifier at column 1"
+    );
+}
+
+#[test]
+fn test_format_section() {
+    let mut props = new_properties();
+    props.insert("temp", Value::number(12.3, Unit::Celsius, Some(0)));
+    let format = Format::parse("{temp}{?rain.1h: ☔{rain.1h}mm}").unwrap();
+    assert_eq!(format.keys(), vec!["temp", "rain.1h", "rain.1h"]);
+    assert_eq!(format.render(&props, Markup::None), "12");
+    props.insert("rain.1h", Value::number(0.5, Unit::Millimeter, None));
+    assert_eq!(format.render(&props, Markup::None), "12 ☔0.5mm");
+}
+
+#[test]
+fn test_format_spec() {
+    let mut props = new_properties();
+    props.insert("temp", Value::number(-3.25, Unit::Celsius, Some(0)));
+    props.insert(
+        "wind_speed",
+        Value::number(4.6, Unit::MeterPerSecond, Some(0)),
+    );
+    props.insert("humidity", Value::number(7.0, Unit::Percent, None));
+    props.insert("city", Value::text("Berlin"));
+    let render = |format: &str| Format::parse(format).unwrap().render(&props, Markup::None);
+    assert_eq!(render("{temp}"), "-3");
+    assert_eq!(render("{temp:.1}"), "-3.2");
+    assert_eq!(render("{temp:06.2}"), "-03.25");
+    assert_eq!(render("{wind_speed:>3}"), "  5");
+    assert_eq!(render("{wind_speed:<3}|"), "5  |");
+    assert_eq!(render("{humidity:02}"), "07");
+    assert_eq!(render("{city:*^10}"), "**Berlin**");
+    assert_eq!(render("{city:8}|"), "Berlin  |");
+    assert_eq!(render("{city:.3}"), "Ber");
+    assert_eq!(
+        Format::parse("{temp:x1}").unwrap_err(),
+        "invalid format specifier `x1` at column 1"
+    );
+}
+
+#[test]
+fn test_values() {
+    let minutes = |m: i64| Value::Duration(chrono::Duration::minutes(m));
+    assert_eq!(minutes(0).to_string(), "+00");
+    assert_eq!(minutes(-12).to_string(), "-12:00");
+    assert_eq!(minutes(75).to_string(), "+01:15:00");
+    assert_eq!(
+        Value::Duration(chrono::Duration::seconds(-3725)).to_string(),
+        "-01:02:05"
+    );
+    let time = chrono::FixedOffset::east_opt(3600)
+        .unwrap()
+        .timestamp_opt(1600000000, 0)
+        .unwrap();
+    assert_eq!(Value::Time(time).to_string(), "13:26");
+    assert_eq!(
+        Value::number(1013.0, Unit::HectoPascal, None).to_string(),
+        "1013"
+    );
+    assert!(Value::text("").is_empty());
+    assert!(!Value::number(0.0, Unit::Millimeter, None).is_empty());
+}
+
+#[test]
+fn test_rules() {
+    let mut props = new_properties();
+    props.insert("temp", Value::number(-2.0, Unit::Celsius, Some(0)));
+    props.insert("main", Value::text("Rain"));
+    let rules: Vec<Rule> = [
+        "wind_speed > 15 -> #ff5555",
+        "temp < 0 → #88c0ff",
+        "main == Rain -> #0000ff",
+    ]
+    .iter()
+    .map(|r| r.parse().unwrap())
+    .collect();
+    assert_eq!(rule::color(&rules, &props), Some("#88c0ff".to_string()));
+    props.insert("temp", Value::number(12.0, Unit::Celsius, Some(0)));
+    assert_eq!(rule::color(&rules, &props), Some("#0000ff".to_string()));
+    props.insert("main", Value::text("Clear"));
+    assert_eq!(rule::color(&rules, &props), None);
+    assert_eq!(
+        Rule::parse("temprature < 0 -> #88c0ff").unwrap_err(),
+        "unknown key `{temprature}` in rule `temprature < 0 -> #88c0ff` (did you mean `{temp}`?)"
+    );
+    assert!(Rule::parse("temp < 0 -> blue").is_err());
+    assert!(Rule::parse("main < Rain -> #0000ff").is_err());
+}
+
+#[test]
+fn test_config() {
+    let config = r#"
+        apikey = "0123456789"
+        format = "-{temp}"
+        poll = 5
+        test = true
+        notify = false
+        color = ["temp < 0 -> #88c0ff", "temp > 30 -> #ff5555"]
+    "#;
+    assert_eq!(
+        config::to_args(config).unwrap(),
+        vec![
+            "--apikey=0123456789",
+            "--format=-{temp}",
+            "--poll=5",
+            "--test",
+            "--color=temp < 0 -> #88c0ff",
+            "--color=temp > 30 -> #ff5555",
+        ]
+    );
+    // tables become options with `key=value`
+    assert_eq!(
+        config::to_args("icon-theme = \"ascii\"\n[icon]\nclear_day = \"O\"\nrain = \"R\"").unwrap(),
+        vec!["--icon-theme=ascii", "--icon=clear_day=O", "--icon=rain=R"]
+    );
+    assert_eq!(
+        config::to_args("icon = { snow = \"S\" }").unwrap(),
+        vec!["--icon=snow=S"]
+    );
+    assert!(config::to_args("[icon.night]\nx = 1").is_err());
+    // command line options override those of the configuration file
+    let args = Args::parse_from(["i3owm", "-c=Paris", "-k", "key", "--location", "Berlin"].iter());
+    assert_eq!(args.location, "Berlin");
+    // ...but repeatable options are appended
+    let args = Args::parse_from(["i3owm", "--format={temp}", "-k", "key", "-f", "{city}"].iter());
+    assert_eq!(args.keys(), vec!["temp", "city"]);
+}
+
+#[test]
+fn test_markup() {
+    let mut props = new_properties();
+    props.insert("city", Value::text("Q&A <City>"));
+    props.insert("temp", Value::number(12.3, Unit::Celsius, Some(0)));
+    let format = Format::parse("<b>{temp}</b> <span size='small'>{city}</span>").unwrap();
+    assert_eq!(
+        format.render(&props, Markup::Pango),
+        "<b>12</b> <span size='small'>Q&amp;A &lt;City&gt;</span>"
+    );
+    assert_eq!(
+        format.render(&props, Markup::None),
+        "<b>12</b> <span size='small'>Q&A <City></span>"
+    );
+}
+
+#[test]
+fn test_block() {
+    let block = Block::new("i3owm", Markup::None, "🌞 12°C");
+    assert_eq!(
+        serde_json::to_string(&block).unwrap(),
+        r#"{"name":"i3owm","markup":"none","full_text":"🌞 12°C"}"#
+    );
+    let block = Block {
+        short_text: Some("12".to_string()),
+        color: Some("#88c0ff".to_string()),
+        ..block
+    };
+    assert_eq!(
+        serde_json::to_string(&block).unwrap(),
+        r##"{"name":"i3owm","markup":"none","full_text":"🌞 12°C","short_text":"12","color":"#88c0ff"}"##
+    );
+    let block = Block {
+        urgent: true,
+        ..Block::new("i3owm", Markup::None, "🔴")
+    };
+    assert_eq!(
+        serde_json::to_string(&block).unwrap(),
+        r#"{"name":"i3owm","markup":"none","full_text":"🔴","urgent":true}"#
+    );
+}
+
+#[test]
+fn test_update_eof() {
+    let block = Block::new("i3owm", Markup::None, "🌞 12°C");
+    let input = "[{\"full_text\":\"x\"}]\n".to_string();
+    let mut io = i3status_ext::io::StringInStdOut::from_string(&input);
+    assert!(i3bar::update(&mut io, 0, false, &block).is_ok());
+    // i3status has exited
+    assert_eq!(
+        i3bar::update(&mut io, 0, false, &block).unwrap_err().kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn test_rotate() {
+    let args = Args::parse_from(
+        [
+            "i3owm", "-k", "key", "-f", "{temp}", "-f", "{city}", "-R", "5",
+        ]
+        .iter(),
+    );
+    let keys = |secs: u64| args.current_format(Duration::from_secs(secs)).keys();
+    assert_eq!(keys(0), vec!["temp"]);
+    assert_eq!(keys(4), vec!["temp"]);
+    assert_eq!(keys(5), vec!["city"]);
+    assert_eq!(keys(10), vec!["temp"]);
+    let args = Args::parse_from(
+        [
+            "i3owm", "-k", "key", "-f", "{temp}", "-f", "{city}", "-R", "0",
+        ]
+        .iter(),
+    );
+    assert_eq!(
+        args.current_format(Duration::from_secs(5)).keys(),
+        vec!["temp"]
+    );
+}
+
+#[test]
+fn test_keys() {
+    assert!(keys::is_known("rain.1h"));
+    assert!(!keys::is_known("temprature"));
+    assert_eq!(keys::similar("temprature"), Some("temp"));
+    assert_eq!(keys::similar("xyz"), None);
+    // forecast keys with hours placeholder
+    assert!(keys::is_known("forecast.3h.temp"));
+    assert!(keys::is_known("forecast.120h.icon"));
+    assert!(!keys::is_known("forecast.4h.temp"));
+    assert!(!keys::is_known("forecast.123h.temp"));
+    assert!(!keys::is_known("forecast.h.temp"));
+    assert!(!keys::is_known("forecast.<n>h.temp"));
+    // every key shall be listed and documented in the README
+    let readme = include_str!("../README.md");
+    for key in keys::KEYS {
+        assert!(keys::list().contains(&format!("{{{}}}", key.name)));
+        assert!(
+            readme.contains(&format!("`{{{}}}`", key.name)),
+            "{}",
+            key.name
+        );
+    }
+    // example values fit into all keys
+    let format: String = keys::KEYS
+        .iter()
+        .flat_map(|k| k.names())
+        .map(|name| format!("{{{}}}", name))
+        .collect();
+    assert!(!Format::parse(&format)
+        .unwrap()
+        .render(&keys::examples(), Markup::None)
+        .contains('{'));
+}
+
+/// escape all braces within a text so that it can be used as literal within a format string
+fn escape(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+#[test]
+fn test_format_escape() {
+    let props = keys::examples();
+    let render = |format: &str| Format::parse(format).unwrap().render(&props, Markup::None);
+    assert_eq!(render("{{{temp}}}"), "{11}");
+    assert_eq!(render("{{city}}"), "{city}");
+    assert_eq!(render("{?temp:{{{temp}}"), "{11");
+    assert_eq!(render("{?temp:{{{temp}}}}"), "{11}");
+    assert_eq!(render("{?temp:}}{{}"), "}{");
+    assert_eq!(
+        Format::parse("{?temp:{temp}}}").unwrap_err(),
+        "unclosed section at column 1"
+    );
+    // nested sections would be ambiguous with escaped braces
+    assert_eq!(
+        Format::parse("{?temp:{?rain.1h:x}}").unwrap_err(),
+        "nested section at column 8"
+    );
+    assert_eq!(render("{?temp:{{?x}}}"), "{?x}");
+    assert_eq!(
+        Format::parse("{temp}}").unwrap_err(),
+        "unmatched `}` at column 7"
+    );
+    // escaped text renders to itself
+    for text in [
+        "{",
+        "}",
+        "{{}}",
+        "fn main() { println!(\"{}\", x); }",
+        "a}b{c",
+    ] {
+        assert_eq!(render(&escape(text)), text);
+        // ...within sections too
+        assert_eq!(render(&format!("{{?temp:{}}}", escape(text))), text);
+    }
+}
+
+#[test]
+fn test_forecast() {
+    let entry = |dt: i64, temp: f64, id: u64, icon: &str, rain: &str| {
+        format!(
+            r#"{{"dt":{},"main":{{"temp":{},"feels_like":{},"pressure":1010,"humidity":70,"temp_min":{},"temp_max":{}}},"weather":[{{"id":{},"main":"Rain","description":"light rain","icon":"{}"}}],"wind":{{"speed":4.6,"deg":300}},"pop":0.42{}}}"#,
+            dt,
+            temp,
+            temp - 1.0,
+            temp - 1.0,
+            temp + 1.0,
+            id,
+            icon,
+            rain
+        )
+    };
+    // now is 2022-06-01 08:00 UTC which is 10:00 in the forecast's timezone (UTC+2)
+    let now = chrono::Utc.with_ymd_and_hms(2022, 6, 1, 8, 0, 0).unwrap();
+    let start = now.timestamp();
+    let list: Vec<String> = (1..=12)
+        .map(|i| {
+            entry(
+                start + i * 3 * 3600,
+                10.0 + i as f64,
+                if i == 9 { 800 } else { 500 },
+                if i == 9 { "01d" } else { "10d" },
+                if i == 1 { r#","rain":{"3h":0.6}"# } else { "" },
+            )
+        })
+        .collect();
+    let json = format!(
+        r#"{{"list":[{}],"city":{{"timezone":7200}}}}"#,
+        list.join(",")
+    );
+    let forecast: forecast::Forecast = serde_json::from_str(&json).unwrap();
+    let mut props = Properties::new();
+    get_forecast(&mut props, &forecast, "metric", 3, &emoji(), now);
+    let render = |format: &str| Format::parse(format).unwrap().render(&props, Markup::None);
+    assert_eq!(
+        render("{forecast.3h.time} {forecast.3h.temp} {forecast.3h.pop}%"),
+        "13:00 11 42%"
+    );
+    assert_eq!(render("{forecast.3h.rain}"), "0.6");
+    assert_eq!(render("{?forecast.6h.rain:rain}"), "");
+    assert_eq!(render("{forecast.36h.temp}"), "22");
+    assert_eq!(render("{?forecast.39h.temp:{forecast.39h.temp}}"), "");
+    // tomorrow (local) starts at 22:00 UTC: entries 5 (01:00) to 12 (22:00)
+    assert_eq!(
+        render("{forecast.tomorrow.temp_min}/{forecast.tomorrow.temp_max}"),
+        "14/23"
+    );
+    // icon from the entry closest to noon (entry 9 at 11:00 UTC = 13:00 local)
+    assert_eq!(render("{forecast.tomorrow.icon}"), "🌞");
+    // today's remaining entries 1 (13:00) to 4 (22:00)
+    assert_eq!(
+        render("{forecast.today.temp_min}/{forecast.today.temp_max}"),
+        "10/15"
+    );
+    // June 3rd is not within the forecast
+    assert_eq!(render("{forecast_strip}"), "We🌦10/15 Th🌞14/23");
+    // current temperature extends today's range
+    props.insert("temp", Value::number(8.0, Unit::Celsius, Some(0)));
+    get_forecast(&mut props, &forecast, "metric", 1, &emoji(), now);
+    let render = |format: &str| Format::parse(format).unwrap().render(&props, Markup::None);
+    assert_eq!(
+        render("{forecast.today.temp_min}/{forecast.today.temp_max}"),
+        "8/15"
+    );
+    assert_eq!(render("{forecast_strip}"), "We🌦10/15");
+}
+
+#[test]
+fn test_air() {
+    let air: air::AirPollution = serde_json::from_str(
+        r#"{"coord":{"lon":13.4,"lat":52.5},"list":[{"dt":1654070400,"main":{"aqi":4},"components":{"co":201.94,"no":0.02,"no2":15.42,"o3":68.66,"so2":0.64,"pm2_5":60.5,"pm10":80.12,"nh3":0.12}}]}"#,
+    )
+    .unwrap();
+    let mut props = Properties::new();
+    assert_eq!(get_air(&mut props, &air, &emoji()), 4);
+    let format = Format::parse("{aqi_icon}{aqi} {pm2_5} {pm10} {o3} {no2}").unwrap();
+    assert_eq!(format.render(&props, Markup::None), "🔴4 60 80 69 15");
+    // no data removes all values
+    let air: air::AirPollution = serde_json::from_str(r#"{"list":[]}"#).unwrap();
+    assert_eq!(get_air(&mut props, &air, &emoji()), 0);
+    assert_eq!(format.render(&props, Markup::None), "    ");
+}
+
+#[test]
+fn test_uv() {
+    let mut props = Properties::new();
+    let format = Format::parse("{uv_icon}{uv} {uv_level}").unwrap();
+    for (uvi, expected) in [
+        (0.0, "🟢0 low"),
+        (3.2, "🟡3 moderate"),
+        (6.8, "🟠7 high"),
+        (10.1, "🔴10 very high"),
+        (11.0, "🟣11 extreme"),
+    ] {
+        let onecall: onecall::OneCall =
+            serde_json::from_str(&format!(r#"{{"current":{{"dt":0,"uvi":{}}}}}"#, uvi)).unwrap();
+        get_onecall(&mut props, &onecall, &emoji());
+        assert_eq!(format.render(&props, Markup::None), expected);
+    }
+    let rule = Rule::parse("uv_level == extreme -> #c040ff").unwrap();
+    assert!(rule.matches(&props));
+    let onecall: onecall::OneCall = serde_json::from_str("{}").unwrap();
+    get_onecall(&mut props, &onecall, &emoji());
+    assert!(!props.has_value("uv"));
+}
+
+#[test]
+fn test_alerts() {
+    let onecall: onecall::OneCall = serde_json::from_str(
+        r#"{"alerts":[{"sender_name":"DWD","event":"Wind warning","start":1654070400,"end":1654113600,"description":"There is a risk of gusts.","tags":["Wind"]}]}"#,
+    )
+    .unwrap();
+    let mut props = Properties::new();
+    get_onecall(&mut props, &onecall, &emoji());
+    let format = Format::parse("{?alert:{alert_icon}{alert} ({alert_count})}").unwrap();
+    assert_eq!(format.render(&props, Markup::None), "⚠Wind warning (1)");
+    // every alert gets announced exactly once
+    let mut notify = Notify::new(true);
+    assert_eq!(notify.unannounced(&onecall.alerts).len(), 1);
+    assert_eq!(notify.unannounced(&onecall.alerts).len(), 0);
+    let mut alerts = onecall.alerts.clone();
+    alerts.push(onecall::Alert {
+        event: "Thunderstorm warning".to_string(),
+        ..onecall.alerts[0].clone()
+    });
+    assert_eq!(notify.unannounced(&alerts), vec![&alerts[1]]);
+    // no alerts
+    let onecall: onecall::OneCall = serde_json::from_str("{}").unwrap();
+    get_onecall(&mut props, &onecall, &emoji());
+    assert_eq!(format.render(&props, Markup::None), "");
+    assert_eq!(props.get("alert_count").unwrap().to_string(), "0");
+}
+
+/// sample weather update (Berlin, 2022-06-01 12:00 local time)
+fn current_weather() -> openweathermap::CurrentWeather {
+    serde_json::from_str(
+        r#"{"coord":{"lon":13.41,"lat":52.52},"weather":[{"id":802,"main":"Clouds","description":"scattered clouds","icon":"03d"}],"base":"stations","main":{"temp":20.0,"feels_like":19.3,"temp_min":18.9,"temp_max":21.1,"pressure":1015,"humidity":50,"sea_level":1015,"grnd_level":1011},"visibility":10000,"wind":{"speed":4.1,"deg":290,"gust":7.2},"clouds":{"all":40},"dt":1654077600,"sys":{"type":2,"id":2011538,"country":"DE","sunrise":1654051200,"sunset":1654110000},"timezone":7200,"id":2950159,"name":"Berlin","cod":200}"#,
+    )
+    .unwrap()
+}
+
+fn render(props: &Properties, format: &str) -> String {
+    Format::parse(format).unwrap().render(props, Markup::None)
+}
+
+#[test]
+fn test_sun() {
+    let w = current_weather();
+    let mut props = Properties::new();
+    // 12:00 local time
+    get_sun(
+        &mut props,
+        &w,
+        &emoji(),
+        chrono::Utc.timestamp_opt(w.dt, 0).unwrap(),
+    );
+    assert_eq!(
+        render(
+            &props,
+            "{sunrise} {sunset} {daylight} {daylight_left} {sun_next}"
+        ),
+        "04:40 21:00 16:20 9:00 🌇 in 9:00"
+    );
+    // 23:30 local time
+    get_sun(
+        &mut props,
+        &w,
+        &emoji(),
+        chrono::Utc.timestamp_opt(1654119000, 0).unwrap(),
+    );
+    assert_eq!(
+        render(&props, "{?daylight_left:{daylight_left}}{sun_next}"),
+        "🌅 in 5:10"
+    );
+    // 04:00 local time
+    get_sun(
+        &mut props,
+        &w,
+        &emoji(),
+        chrono::Utc.timestamp_opt(1654048800, 0).unwrap(),
+    );
+    assert_eq!(render(&props, "{sun_next}"), "🌅 in 0:40");
+    // polar night
+    let mut w = w;
+    w.sys.sunrise = 0;
+    w.sys.sunset = 0;
+    get_sun(&mut props, &w, &emoji(), chrono::Utc::now());
+    assert!(!props.has_value("sunrise"));
+    assert!(!props.has_value("sun_next"));
+}
+
+#[test]
+fn test_moon() {
+    let mut w = current_weather();
+    let mut props = Properties::new();
+    // phases of June 2022
+    for (time, expected, illumination) in [
+        ((6, 7, 14, 48), "🌓 first quarter", 50.0),
+        ((6, 14, 11, 52), "🌕 full moon", 100.0),
+        ((6, 21, 3, 11), "🌗 last quarter", 50.0),
+        ((6, 29, 2, 52), "🌑 new moon", 0.0),
+    ] {
+        let (month, day, hour, minute) = time;
+        let now = chrono::Utc
+            .with_ymd_and_hms(2022, month, day, hour, minute, 0)
+            .unwrap();
+        get_moon(&mut props, &w, &emoji(), now);
+        assert_eq!(render(&props, "{moon_icon} {moon_phase}"), expected);
+        // illumination is accurate to about 2%
+        match props.get("moon_illumination") {
+            Some(Value::Number { value, .. }) => assert!((value - illumination).abs() < 2.0),
+            _ => panic!("no illumination"),
+        }
+    }
+    // icon is mirrored on the southern hemisphere
+    let now = chrono::Utc.with_ymd_and_hms(2022, 6, 10, 0, 0, 0).unwrap();
+    get_moon(&mut props, &w, &emoji(), now);
+    assert_eq!(
+        render(&props, "{moon_icon} {moon_phase}"),
+        "🌔 waxing gibbous"
+    );
+    w.coord.lat = -33.87;
+    w.coord.lon = 151.21;
+    w.timezone = 36000;
+    get_moon(&mut props, &w, &emoji(), now);
+    assert_eq!(
+        render(&props, "{moon_icon} {moon_phase}"),
+        "🌖 waxing gibbous"
+    );
+    // the full moon rises around sunset and sets around sunrise
+    let w = current_weather();
+    let now = chrono::Utc.with_ymd_and_hms(2022, 6, 14, 10, 0, 0).unwrap();
+    get_moon(&mut props, &w, &emoji(), now);
+    assert_eq!(render(&props, "{moonrise} {moonset}"), "22:09 04:13");
+    let (rise, set) = moon::rise_set(now, w.coord.lat, w.coord.lon);
+    for time in [rise.unwrap(), set.unwrap()] {
+        assert!(moon::altitude(time, w.coord.lat, w.coord.lon).abs() < 0.5);
+    }
+}
+
+#[test]
+fn test_comfort() {
+    let mut w = current_weather();
+    let mut props = Properties::new();
+    let format =
+        "{feels_like} {dew_point} {humidex}{?heat_index: {heat_index}}{?wind_chill: {wind_chill}}";
+    get_weather(&mut props, &w, "metric", "en", Compass::Eight, &emoji());
+    assert_eq!(render(&props, format), "19 9 21");
+    // hot and humid
+    w.main.temp = 32.0;
+    w.main.humidity = 70.0;
+    get_weather(&mut props, &w, "metric", "en", Compass::Eight, &emoji());
+    assert_eq!(render(&props, "{heat_index}"), "40");
+    assert!(!props.has_value("wind_chill"));
+    // cold and windy
+    w.main.temp = -5.0;
+    w.wind.speed = 20.0 / 3.6;
+    get_weather(&mut props, &w, "metric", "en", Compass::Eight, &emoji());
+    assert_eq!(render(&props, "{wind_chill}"), "-12");
+    assert!(!props.has_value("heat_index"));
+    // same in imperial units
+    w.main.temp = 23.0;
+    w.wind.speed = 20.0 / 1.609344;
+    get_weather(&mut props, &w, "imperial", "en", Compass::Eight, &emoji());
+    assert_eq!(render(&props, "{wind_chill}{temp_unit}"), "11°F");
+    // same in standard units
+    w.main.temp = 268.15;
+    w.wind.speed = 20.0 / 3.6;
+    get_weather(&mut props, &w, "standard", "en", Compass::Eight, &emoji());
+    assert_eq!(render(&props, "{wind_chill}{temp_unit}"), "262K");
+}
+
+#[test]
+fn test_beaufort() {
+    let mut w = current_weather();
+    let mut props = Properties::new();
+    let format = "{wind_bar}{beaufort} {beaufort_name}{?wind_gust: ({wind_gust})}";
+    for (speed, expected) in [
+        (0.0, "▁0 calm"),
+        (0.5, "▁1 light air"),
+        (4.1, "▂3 gentle breeze"),
+        (10.7, "▄5 fresh breeze"),
+        (17.2, "▅8 gale"),
+        (40.0, "█12 hurricane"),
+    ] {
+        w.wind.speed = speed;
+        w.wind.gust = None;
+        get_weather(&mut props, &w, "metric", "en", Compass::Eight, &emoji());
+        assert_eq!(render(&props, format), expected);
+    }
+    // speed gets converted from mi/h, names get localized
+    w.wind.speed = 25.0;
+    w.wind.gust = Some(38.0);
+    get_weather(&mut props, &w, "imperial", "de", Compass::Eight, &emoji());
+    assert_eq!(render(&props, format), "▄6 starker Wind (38)");
+    // unknown languages fall back to English
+    get_weather(&mut props, &w, "imperial", "xx", Compass::Eight, &emoji());
+    assert_eq!(render(&props, "{beaufort_name}"), "strong breeze");
+}
+
+#[test]
+fn test_pressure() {
+    let mut w = current_weather();
+    let mut props = Properties::new();
+    get_weather(&mut props, &w, "metric", "en", Compass::Eight, &emoji());
+    assert_eq!(
+        render(
+            &props,
+            "{clouds}% {pressure} {pressure_sea} {pressure_ground}"
+        ),
+        "40% 1015 1015 1011"
+    );
+    w.main.grnd_level = None;
+    get_weather(&mut props, &w, "metric", "en", Compass::Eight, &emoji());
+    assert!(!props.has_value("pressure_ground"));
+    // trend needs at least 2¼ hours of history
+    let mut history = History::new();
+    let mut observe = |dt: i64, pressure: f64| {
+        history.push(history::Observation {
+            dt,
+            temp: 20.0,
+            pressure,
+        });
+        get_trends(&mut props, &history, "metric");
+        render(&props, "{?pressure_trend:{pressure_trend}}")
+    };
+    assert_eq!(observe(0, 1015.0), "");
+    assert_eq!(observe(3600, 1014.8), "");
+    assert_eq!(observe(7200, 1014.6), "");
+    assert_eq!(observe(9000, 1014.3), "→");
+    // falls 1.5 hPa within 3 hours
+    assert_eq!(observe(10800, 1013.5), "↓");
+    // only the last 3 hours count
+    assert_eq!(observe(14400, 1014.5), "→");
+    assert_eq!(observe(18000, 1015.8), "↑");
+}
+
+#[test]
+fn test_history() {
+    let mut history = History::new();
+    let pressure = |o: &history::Observation| o.pressure;
+    assert_eq!(history.change(pressure, chrono::Duration::hours(1)), None);
+    // same update is remembered only once
+    for _ in 0..3 {
+        history.push(history::Observation {
+            dt: 0,
+            temp: 20.0,
+            pressure: 1000.0,
+        });
+    }
+    history.push(history::Observation {
+        dt: 1800,
+        temp: 20.0,
+        pressure: 1001.0,
+    });
+    // change is not extrapolated from half an hour
+    assert_eq!(history.change(pressure, chrono::Duration::hours(1)), None);
+    history.push(history::Observation {
+        dt: 2700,
+        temp: 20.0,
+        pressure: 1001.5,
+    });
+    assert_eq!(
+        history.change(pressure, chrono::Duration::hours(1)),
+        Some(1.5)
+    );
+    // capacity is limited (256 updates every minute cover 4¼ hours)
+    for dt in 1..1000 {
+        history.push(history::Observation {
+            dt: 2700 + dt * 60,
+            temp: 20.0,
+            pressure: 1001.5,
+        });
+    }
+    assert_eq!(
+        history.change(pressure, chrono::Duration::hours(4)),
+        Some(0.0)
+    );
+    assert_eq!(history.change(pressure, chrono::Duration::hours(6)), None);
+}
+
+#[test]
+fn test_temp_trend() {
+    let mut props = Properties::new();
+    let mut history = History::new();
+    let mut observe = |dt: i64, temp: f64, units: &str| {
+        history.push(history::Observation {
+            dt,
+            temp,
+            pressure: 1015.0,
+        });
+        get_trends(&mut props, &history, units);
+        render(&props, "{?temp_trend:{temp_trend}{temp_delta_1h}}")
+    };
+    assert_eq!(observe(0, 20.0, "metric"), "");
+    assert_eq!(observe(1200, 20.2, "metric"), "");
+    assert_eq!(observe(1800, 20.2, "metric"), "");
+    // the change is measured, not extrapolated
+    assert_eq!(observe(2700, 20.4, "metric"), "→0.4");
+    assert_eq!(observe(3600, 21.5, "metric"), "↗1.5");
+    assert_eq!(observe(7200, 19.0, "metric"), "↘-2.5");
+    // half a degree Celsius is 0.9°F
+    let mut history = History::new();
+    let mut observe = |dt: i64, temp: f64| {
+        history.push(history::Observation {
+            dt,
+            temp,
+            pressure: 1015.0,
+        });
+        get_trends(&mut props, &history, "imperial");
+        render(&props, "{temp_trend}")
+    };
+    observe(0, 68.0);
+    assert_eq!(observe(3600, 68.8), "→");
+    assert_eq!(observe(7200, 69.8), "↗");
+}
+
+#[test]
+fn test_nowcast() {
+    let now = chrono::Utc.timestamp_opt(1654077600, 0).unwrap();
+    // nowcast of 60 minutes which started 2 minutes ago
+    let onecall = |precipitation: &dyn Fn(i64) -> f64| -> onecall::OneCall {
+        let minutely: Vec<String> = (-2..58)
+            .map(|m| {
+                format!(
+                    r#"{{"dt":{},"precipitation":{}}}"#,
+                    now.timestamp() + m * 60,
+                    precipitation(m)
+                )
+            })
+            .collect();
+        serde_json::from_str(&format!(r#"{{"minutely":[{}]}}"#, minutely.join(","))).unwrap()
+    };
+    let mut props = Properties::new();
+    let mut nowcast = |o: &onecall::OneCall| {
+        let start = get_nowcast(&mut props, o, now);
+        (start, render(&props, "{rain_in}|{rain_nowcast}"))
+    };
+    assert_eq!(
+        nowcast(&onecall(&|_| 0.0)),
+        (None, "dry for 58 min|▁▁▁▁▁▁▁▁▁▁▁▁".to_string())
+    );
+    assert_eq!(
+        nowcast(&onecall(&|m| if (12..20).contains(&m) { 1.5 } else { 0.0 })),
+        (Some(12), "rain in 12 min|▁▁▄▄▁▁▁▁▁▁▁▁".to_string())
+    );
+    assert_eq!(
+        nowcast(&onecall(&|m| if m < 5 { 0.3 } else { 0.0 })),
+        (Some(0), "rain stops in 5 min|▂▁▁▁▁▁▁▁▁▁▁▁".to_string())
+    );
+    assert_eq!(
+        nowcast(&onecall(&|_| 20.0)).1,
+        "rain for 58 min|████████████"
+    );
+    // no nowcast
+    assert_eq!(
+        nowcast(&serde_json::from_str("{}").unwrap()),
+        (None, "|".to_string())
+    );
+    // upcoming rain gets announced once until it is dry again
+    let mut notify = Notify::new(true);
+    assert!(!notify.rain_due(Some(20), 15));
+    assert!(notify.rain_due(Some(15), 15));
+    assert!(!notify.rain_due(Some(10), 15));
+    assert!(!notify.rain_due(Some(0), 15));
+    assert!(!notify.rain_due(None, 15));
+    assert!(notify.rain_due(Some(5), 15));
+}
+
+#[test]
+fn test_compass() {
+    // rounding to the nearest sector (also around north)
+    for (deg, eight, sixteen) in [
+        (0.0, "N", "N"),
+        (11.0, "N", "N"),
+        (12.0, "N", "NNE"),
+        (22.0, "N", "NNE"),
+        (23.0, "NE", "NNE"),
+        (44.0, "NE", "NE"),
+        (180.0, "S", "S"),
+        (200.0, "S", "SSW"),
+        (337.0, "NW", "NNW"),
+        (349.0, "N", "N"),
+        (359.9, "N", "N"),
+        (360.0, "N", "N"),
+    ] {
+        assert_eq!(Compass::Eight.name("en", deg), eight, "{}°", deg);
+        assert_eq!(Compass::Sixteen.name("en", deg), sixteen, "{}°", deg);
+    }
+    // localized names
+    assert_eq!(Compass::Eight.name("de", 90.0), "O");
+    assert_eq!(Compass::Sixteen.name("fr", 292.5), "ONO");
+    assert_eq!(Compass::Eight.name("xx", 135.0), "SE");
+    // wind icon is rounded too
+    let mut w = current_weather();
+    let mut props = Properties::new();
+    w.wind.deg = 350.0;
+    get_weather(&mut props, &w, "metric", "de", Compass::Sixteen, &emoji());
+    assert_eq!(render(&props, "{wind} {wind_icon}"), "N ↓");
+}
+
+#[test]
+fn test_icons() {
+    let w = current_weather();
+    // themes
+    let mut props = Properties::new();
+    get_weather(&mut props, &w, "metric", "en", Compass::Eight, &emoji());
+    assert_eq!(render(&props, "{icon} {wind_icon}"), "⛅ →");
+    let ascii = Icons::new(Theme::Ascii, &[]);
+    get_weather(&mut props, &w, "metric", "en", Compass::Eight, &ascii);
+    assert_eq!(render(&props, "{icon} {wind_icon}"), "~ >");
+    let nerd = Icons::new(Theme::Nerd, &[]);
+    assert_eq!(nerd.weather(&condition(800, "01n")), "\u{e32b}");
+    let weather_icons = Icons::new(Theme::WeatherIcons, &[]);
+    assert_eq!(weather_icons.weather(&condition(601, "13d")), "\u{f01b}");
+    // unknown conditions
+    assert_eq!(emoji().weather(&condition(900, "")), "❔");
+    // replacements
+    let args = Args::parse_from(
+        [
+            "i3owm",
+            "-k",
+            "key",
+            "--icon-theme",
+            "ascii",
+            "--icon",
+            "scattered_clouds=SC",
+            "--icon=wind_w = W",
+        ]
+        .iter(),
+    );
+    let icons = Icons::new(args.icon_theme, &args.icon);
+    get_weather(&mut props, &w, "metric", "en", Compass::Eight, &icons);
+    assert_eq!(render(&props, "{icon} {wind_icon}"), "SC W");
+    assert_eq!(icons.weather(&condition(800, "01d")), "*");
+    assert!("few_cloud=x"
+        .parse::<icons::Icon>()
+        .unwrap_err()
+        .contains("did you mean `few_clouds`?"));
+    assert!("few_clouds".parse::<icons::Icon>().is_err());
+    assert!(Args::try_parse_from(["i3owm", "-k", "key", "--icon", "sun=S"].iter()).is_err());
+    // ISS icons
+    let icons = Icons::new(Theme::Emoji, &["iss=ISS".parse().unwrap()]);
+    let mut props = Properties::new();
+    let spot = open_notify::Spot {
+        risetime: chrono::Local::now() + chrono::Duration::minutes(5),
+        duration: chrono::Duration::minutes(4),
+    };
+    get_spots(
+        &mut props,
+        &vec![spot],
+        30,
+        true,
+        None,
+        false,
+        &Level::SOON,
+        &icons,
+    );
+    assert_eq!(render(&props, "{iss_icon}"), "ISS");
+    // blinking while the ISS is visible
+    let icons = Icons::new(Theme::Ascii, &[]);
+    let spot = open_notify::Spot {
+        risetime: chrono::Local::now() - chrono::Duration::minutes(1),
+        duration: chrono::Duration::minutes(4),
+    };
+    get_spots(
+        &mut props,
+        &vec![spot],
+        30,
+        true,
+        None,
+        true,
+        &Level::SOON,
+        &icons,
+    );
+    assert_eq!(render(&props, "{iss_icon}"), "o");
+    // the ascii theme needs no symbols at all
+    for name in icons::NAMES {
+        assert!(icons.get(name).is_ascii());
+    }
+    // sun, moon, alert and risk icons are themed too
+    assert_eq!(icons.moon(4), "O");
+    assert_eq!(icons.risk(1), "+");
+    assert_eq!(icons.risk(9), "!!");
+    let mut props = Properties::new();
+    get_sun(
+        &mut props,
+        &w,
+        &icons,
+        chrono::Utc.timestamp_opt(1654048800, 0).unwrap(),
+    );
+    get_moon(
+        &mut props,
+        &w,
+        &icons,
+        chrono::Utc.with_ymd_and_hms(2022, 6, 14, 12, 0, 0).unwrap(),
+    );
+    assert_eq!(render(&props, "{sun_next} {moon_icon}"), "rise in 0:40 O");
+}
+
+#[test]
+fn test_conditions() {
+    let icons = Icons::new(Theme::Ascii, &[]);
+    let icon = |id: u64, icon: &str| icons.weather(&condition(id, icon)).to_string();
+    // day and night
+    assert_eq!(icon(800, "01d"), icons.get("clear_day"));
+    assert_eq!(icon(800, "01n"), icons.get("clear_night"));
+    assert_eq!(icon(801, "02n"), icons.get("few_clouds_night"));
+    assert_eq!(icon(500, "10d"), icons.get("rain"));
+    assert_eq!(icon(501, "10n"), icons.get("rain_night"));
+    assert_eq!(icon(804, "04n"), icons.get("broken_clouds"));
+    // condition IDs within the same icon code
+    assert_eq!(icon(201, "11d"), icons.get("thunderstorm_rain"));
+    assert_eq!(icon(231, "11d"), icons.get("thunderstorm_rain"));
+    assert_eq!(icon(211, "11d"), icons.get("thunderstorm"));
+    assert_eq!(icon(301, "09d"), icons.get("drizzle"));
+    assert_eq!(icon(503, "10d"), icons.get("heavy_rain"));
+    assert_eq!(icon(511, "13d"), icons.get("freezing_rain"));
+    assert_eq!(icon(521, "09n"), icons.get("shower_rain"));
+    assert_eq!(icon(612, "13d"), icons.get("sleet"));
+    assert_eq!(icon(622, "13d"), icons.get("snow"));
+    assert_eq!(icon(741, "50d"), icons.get("mist"));
+    assert_eq!(icon(761, "50d"), icons.get("dust"));
+    assert_eq!(icon(771, "50d"), icons.get("squall"));
+    assert_eq!(icon(781, "50d"), icons.get("tornado"));
+    // all conditions
+    let mut w = current_weather();
+    let mut props = Properties::new();
+    w.weather = vec![condition(500, "10n"), condition(701, "50n")];
+    w.weather[0].description = "light rain".to_string();
+    w.weather[1].description = "mist".to_string();
+    get_weather(&mut props, &w, "metric", "en", Compass::Eight, &icons);
+    assert_eq!(
+        render(&props, "{icon} {description} ({conditions})"),
+        "// light rain (light rain, mist)"
+    );
+}
+
+#[test]
+fn test_fetch_latest() {
+    let (tx, rx) = std::sync::mpsc::channel::<Result<u8, String>>();
+    let receiver: Option<fetch::Receiver<u8>> = Some(rx);
+    // neither loading nor errors are an update
+    tx.send(Err(openweathermap::LOADING.to_string())).unwrap();
+    assert_eq!(fetch::latest(&receiver, "test"), None);
+    tx.send(Err("401 Unauthorized".to_string())).unwrap();
+    assert_eq!(fetch::latest(&receiver, "test"), None);
+    tx.send(Ok(42)).unwrap();
+    assert_eq!(fetch::latest(&receiver, "test"), Some(42));
+    assert_eq!(fetch::latest(&receiver, "test"), None);
+    // source has not been started
+    assert_eq!(fetch::latest::<u8>(&None, "test"), None);
+}
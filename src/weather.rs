@@ -1,124 +1,655 @@
 use chrono::prelude::*;
-use std::collections::HashMap;
-pub use open_notify::{DayTime};
+
+use crate::air::AirPollution;
+use crate::forecast::{Entry, Forecast};
+use crate::history::History;
+use crate::icons::Icons;
+use crate::keys;
+use crate::locale::{self, Compass};
+use crate::moon;
+use crate::onecall::{Minutely, OneCall};
+use crate::property::{Properties, Unit, Value};
 
 /// update properties map with new weather update data
 /// #### Parameters
 /// - `props`: property map to add data into
 /// - `current`: current weather update
 /// - `units`: maximum level of spotting display that is wanted (either `"standard"`, `"metric"` or `"imperial"`
+/// - `lang`: two character language code of texts
+/// - `compass`: number of points of the compass of `{wind}`
+/// - `icons`: icons of the selected theme
 pub fn get_weather(
-    props: &mut HashMap<&str, String>,
+    props: &mut Properties,
     current: &openweathermap::CurrentWeather,
     units: &str,
+    lang: &str,
+    compass: Compass,
+    icons: &Icons,
 ) {
-    fn dir(current: &openweathermap::CurrentWeather) -> usize {
-        (current.wind.deg as usize % 360) / 45
+    let temp = Unit::temperature(units);
+    let speed = Unit::speed(units);
+    let update: DateTime<Local> = DateTime::from(Utc.timestamp_opt(current.dt, 0).unwrap());
+
+    props.insert("update", Value::Time(update.into()));
+    props.insert("city", Value::text(&current.name));
+    props.insert("main", Value::text(&current.weather[0].main));
+    props.insert("description", Value::text(&current.weather[0].description));
+    props.insert(
+        "conditions",
+        Value::Text(
+            current
+                .weather
+                .iter()
+                .map(|w| w.description.as_str())
+                .collect::<Vec<&str>>()
+                .join(", "),
+        ),
+    );
+    props.insert("icon", Value::icon(icons.weather(&current.weather[0])));
+    props.insert(
+        "pressure",
+        Value::number(current.main.pressure, Unit::HectoPascal, None),
+    );
+    let pressures = [
+        ("pressure_sea", current.main.sea_level),
+        ("pressure_ground", current.main.grnd_level),
+    ];
+    for (key, pressure) in pressures {
+        match pressure {
+            Some(p) => props.insert(key, Value::number(p, Unit::HectoPascal, None)),
+            None => props.remove(key),
+        }
     }
-    // get a unicode symbol that matches the OWM icon
-    fn icon(icon_id: &str) -> &str {
-        let icons: HashMap<&str, &str> = [
-            ("01d", "🌞"),
-            ("01n", "🌛"),
-            ("02d", "🌤"),
-            ("02n", "🌤"),
-            ("03d", "⛅"),
-            ("03n", "⛅"),
-            ("04d", "⛅"),
-            ("04n", "⛅"),
-            ("09d", "🌧"),
-            ("09n", "🌧"),
-            ("10d", "🌦"),
-            ("10n", "🌦"),
-            ("11d", "🌩"),
-            ("11n", "🌩"),
-            ("13d", "❄"),
-            ("13n", "❄"),
-            ("50d", "🌫"),
-            ("50n", "🌫"),
-        ]
-        .iter()
-        .cloned()
-        .collect();
-        return icons.get(&icon_id).unwrap_or(&"🚫");
+    props.insert(
+        "humidity",
+        Value::number(current.main.humidity, Unit::Percent, None),
+    );
+    props.insert(
+        "wind_deg",
+        Value::number(current.wind.deg, Unit::Degree, None),
+    );
+    props.insert("wind", Value::text(compass.name(lang, current.wind.deg)));
+    props.insert(
+        "wind_icon",
+        Value::icon(icons.wind(Compass::Eight.sector(current.wind.deg))),
+    );
+    props.insert_unit("deg_unit", "wind_deg");
+    props.insert(
+        "wind_speed",
+        Value::number(current.wind.speed, speed, Some(0)),
+    );
+    match current.wind.gust {
+        Some(gust) => props.insert("wind_gust", Value::number(gust, speed, Some(0))),
+        None => props.remove("wind_gust"),
     }
-    let update: DateTime<Local> = DateTime::from(Utc.timestamp(current.dt, 0));
-
-    props.insert("{update}", update.format("%H:%M").to_string());
-    props.insert("{city}", current.name.as_str().to_string());
-    props.insert("{main}", current.weather[0].main.as_str().to_string());
-    props.insert(
-        "{description}",
-        current.weather[0].description.as_str().to_string(),
-    );
-    props.insert("{icon}", icon(&current.weather[0].icon).to_string());
-    props.insert("{pressure}", current.main.pressure.to_string());
-    props.insert("{humidity}", current.main.humidity.to_string());
-    props.insert("{wind}", current.wind.deg.to_string());
-    props.insert("{wind_deg}", current.wind.deg.to_string());
-    props.insert("{wind}", {
-        let directions = ["N", "NO", "O", "SO", "S", "SW", "W", "NW"];
-        directions[dir(current)].to_string()
-    });
-    props.insert("{wind_icon}", {
-        let icons = ["↓", "↙", "←", "↖", "↑", "↗", "→", "↘"];
-        icons[dir(current)].to_string()
+    // upper limits of the Beaufort numbers 0–11 in m/s
+    let limits = [
+        0.5, 1.6, 3.4, 5.5, 8.0, 10.8, 13.9, 17.2, 20.8, 24.5, 28.5, 32.7,
+    ];
+    let ms = speed.in_kilometer_per_hour(current.wind.speed) / 3.6;
+    let beaufort = limits.iter().filter(|limit| ms >= **limit).count();
+    props.insert("beaufort", Value::number(beaufort as f64, Unit::None, None));
+    props.insert(
+        "beaufort_name",
+        Value::text(locale::beaufort_name(lang, beaufort)),
+    );
+    props.insert("wind_bar", {
+        let bars = ["▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+        Value::icon(bars[beaufort * bars.len() / 13])
     });
-    props.insert("{deg_unit}", "°".to_string());
-    props.insert("{wind_speed}", current.wind.speed.round().to_string());
-    props.insert("{visibility}", current.visibility.to_string());
-    props.insert("{visibility_km}", (current.visibility / 1000).to_string());
-    props.insert(
-        "{rain.1h}",
-        match &current.rain {
-            Some(r) => r.h1.unwrap_or(0.0).to_string(),
-            None => "-".to_string(),
-        }
-        .to_string(),
+    props.insert(
+        "visibility",
+        Value::number(current.visibility as f64, Unit::Meter, None),
     );
     props.insert(
-        "{rain.3h}",
-        match &current.rain {
-            Some(r) => r.h3.unwrap_or(0.0).to_string(),
-            None => "-".to_string(),
-        },
+        "visibility_km",
+        Value::number(current.visibility as f64 / 1000.0, Unit::Kilometer, Some(0)),
     );
     props.insert(
-        "{snow.1h}",
-        match &current.snow {
-            Some(r) => r.h1.unwrap_or(0.0).to_string(),
-            None => "-".to_string(),
-        },
+        "clouds",
+        Value::number(current.clouds.all, Unit::Percent, None),
     );
+    let volumes = [
+        ("rain.1h", current.rain.as_ref().and_then(|v| v.h1)),
+        ("rain.3h", current.rain.as_ref().and_then(|v| v.h3)),
+        ("snow.1h", current.snow.as_ref().and_then(|v| v.h1)),
+        ("snow.3h", current.snow.as_ref().and_then(|v| v.h3)),
+    ];
+    for (key, volume) in volumes {
+        match volume {
+            Some(v) => props.insert(key, Value::number(v, Unit::Millimeter, None)),
+            None => props.remove(key),
+        };
+    }
     props.insert(
-        "{snow.3h}",
-        match &current.snow {
-            Some(r) => r.h3.unwrap_or(0.0).to_string(),
-            None => "-".to_string(),
-        },
+        "temp_min",
+        Value::number(current.main.temp_min, temp, Some(0)),
+    );
+    props.insert(
+        "temp_max",
+        Value::number(current.main.temp_max, temp, Some(0)),
+    );
+    props.insert(
+        "feels_like",
+        Value::number(current.main.feels_like, temp, Some(0)),
     );
-    props.insert("{temp_min}", current.main.temp_min.round().to_string());
-    props.insert("{temp_max}", current.main.temp_max.round().to_string());
-    props.insert("{feels_like}", current.main.temp.round().to_string());
-    props.insert("{temp}", current.main.temp.round().to_string());
-    props.insert(
-        "{temp_unit}",
-        match units {
-            "standard" => "K",
-            "metric" => "°C",
-            "imperial" => "°F",
-            _ => "",
+    props.insert("temp", Value::number(current.main.temp, temp, Some(0)));
+    get_comfort(props, current, units);
+    // units are taken from the values they belong to
+    props.insert_unit("temp_unit", "temp");
+    props.insert_unit("speed_unit", "wind_speed");
+}
+
+/// update properties map with comfort metrics derived from temperature, humidity and wind speed
+/// #### Parameters
+/// - `props`: property map to add data into
+/// - `current`: current weather update
+/// - `units`: either `"standard"`, `"metric"` or `"imperial"`
+fn get_comfort(props: &mut Properties, current: &openweathermap::CurrentWeather, units: &str) {
+    let temp = Unit::temperature(units);
+    // all formulas calculate in °C and km/h
+    let t = temp.in_celsius(current.main.temp);
+    let rh = current.main.humidity;
+    let v = Unit::speed(units).in_kilometer_per_hour(current.wind.speed);
+    let mut insert = |key: &str, celsius: Option<f64>| match celsius {
+        Some(c) => props.insert(key, Value::number(temp.of_celsius(c), temp, Some(0))),
+        None => props.remove(key),
+    };
+    // dew point by Magnus formula
+    let dew_point = match rh > 0.0 {
+        true => {
+            let (a, b) = (17.62, 243.12);
+            let gamma = (rh / 100.0).ln() + a * t / (b + t);
+            Some(b * gamma / (a - gamma))
         }
-        .to_string(),
+        false => None,
+    };
+    insert("dew_point", dew_point);
+    // humidex of Environment Canada
+    insert(
+        "humidex",
+        dew_point.map(|td| {
+            t + 0.5555 * (6.11 * (5417.7530 * (1.0 / 273.16 - 1.0 / (273.15 + td))).exp() - 10.0)
+        }),
     );
+    // heat index of the NOAA (calculated in °F) is defined from 27°C (80°F) on
+    let heat_index = match t >= 26.7 {
+        true => {
+            let f = Unit::Fahrenheit.of_celsius(t);
+            let mut hi = -42.379 + 2.04901523 * f + 10.14333127 * rh
+                - 0.22475541 * f * rh
+                - 0.00683783 * f * f
+                - 0.05481717 * rh * rh
+                + 0.00122874 * f * f * rh
+                + 0.00085282 * f * rh * rh
+                - 0.00000199 * f * f * rh * rh;
+            if rh < 13.0 && f <= 112.0 {
+                hi -= (13.0 - rh) / 4.0 * ((17.0 - (f - 95.0).abs()) / 17.0).sqrt();
+            } else if rh > 85.0 && f <= 87.0 {
+                hi += (rh - 85.0) / 10.0 * (87.0 - f) / 5.0;
+            }
+            Some(Unit::Fahrenheit.in_celsius(hi))
+        }
+        false => None,
+    };
+    insert("heat_index", heat_index);
+    // wind chill of the JAG/TI is defined up to 10°C and from 4.8 km/h on
+    let wind_chill = match t <= 10.0 && v > 4.8 {
+        true => Some(13.12 + 0.6215 * t - 11.37 * v.powf(0.16) + 0.3965 * t * v.powf(0.16)),
+        false => None,
+    };
+    insert("wind_chill", wind_chill);
+}
+
+/// update properties map with trends from the history of weather updates
+/// #### Parameters
+/// - `props`: property map to add data into
+/// - `history`: recent observations
+/// - `units`: either `"standard"`, `"metric"` or `"imperial"`
+pub fn get_trends(props: &mut Properties, history: &History, units: &str) {
+    let temp = Unit::temperature(units);
+    // temperature change within the last hour
+    match history.change(|o| o.temp, chrono::Duration::hours(1)) {
+        Some(change) => {
+            // half a degree Celsius is steady
+            let steady = temp.of_celsius(0.5) - temp.of_celsius(0.0);
+            props.insert(
+                "temp_trend",
+                Value::icon(match change {
+                    c if c >= steady => "↗",
+                    c if c <= -steady => "↘",
+                    _ => "→",
+                }),
+            );
+            props.insert("temp_delta_1h", Value::number(change, temp, Some(1)));
+        }
+        None => {
+            props.remove("temp_trend");
+            props.remove("temp_delta_1h");
+        }
+    }
+    // pressure tendency within 3 hours like meteorologists use it
+    match history.change(|o| o.pressure, chrono::Duration::hours(3)) {
+        Some(change) => props.insert(
+            "pressure_trend",
+            Value::icon(match change {
+                c if c >= 1.0 => "↑",
+                c if c <= -1.0 => "↓",
+                _ => "→",
+            }),
+        ),
+        None => props.remove("pressure_trend"),
+    }
+}
+
+/// update properties map with new forecast data
+/// #### Parameters
+/// - `props`: property map to add data into
+/// - `forecast`: latest 5 day / 3 hour forecast
+/// - `units`: either `"standard"`, `"metric"` or `"imperial"`
+/// - `days`: number of days within `{forecast_strip}` (including today)
+/// - `icons`: icons of the selected theme
+/// - `now`: current time
+pub fn get_forecast(
+    props: &mut Properties,
+    forecast: &Forecast,
+    units: &str,
+    days: usize,
+    icons: &Icons,
+    now: DateTime<Utc>,
+) {
+    let temp = Unit::temperature(units);
+    let speed = Unit::speed(units);
+    let tz = FixedOffset::east_opt(forecast.city.timezone as i32)
+        .unwrap_or(FixedOffset::east_opt(0).unwrap());
+    let time = |dt: i64| Utc.timestamp_opt(dt, 0).unwrap().with_timezone(&tz);
+    // insert forecast for the next hours in steps of 3 hours
+    for hours in keys::hours() {
+        let key = |field: &str| format!("forecast.{}h.{}", hours, field);
+        let target = (now + chrono::Duration::hours(hours as i64)).timestamp();
+        // find entry which is closest to target time but not further away than the step size
+        let entry = forecast
+            .list
+            .iter()
+            .min_by_key(|e| (e.dt - target).abs())
+            .filter(|e| (e.dt - target).abs() <= 90 * 60);
+        let fields = [
+            "time",
+            "temp",
+            "feels_like",
+            "icon",
+            "description",
+            "humidity",
+            "wind_speed",
+            "pop",
+            "rain",
+        ];
+        let entry = match entry {
+            Some(entry) => entry,
+            None => {
+                for field in fields {
+                    props.remove(&key(field));
+                }
+                continue;
+            }
+        };
+        props.insert(&key("time"), Value::Time(time(entry.dt)));
+        props.insert(&key("temp"), Value::number(entry.main.temp, temp, Some(0)));
+        props.insert(
+            &key("feels_like"),
+            Value::number(entry.main.feels_like, temp, Some(0)),
+        );
+        props.insert(&key("icon"), Value::icon(icons.weather(&entry.weather[0])));
+        props.insert(
+            &key("description"),
+            Value::text(&entry.weather[0].description),
+        );
+        props.insert(
+            &key("humidity"),
+            Value::number(entry.main.humidity, Unit::Percent, None),
+        );
+        props.insert(
+            &key("wind_speed"),
+            Value::number(entry.wind.speed, speed, Some(0)),
+        );
+        props.insert(
+            &key("pop"),
+            Value::number(entry.pop.unwrap_or(0.0) * 100.0, Unit::Percent, Some(0)),
+        );
+        match entry.rain.as_ref().and_then(|v| v.h3) {
+            Some(v) => props.insert(&key("rain"), Value::number(v, Unit::Millimeter, None)),
+            None => props.remove(&key("rain")),
+        }
+    }
+    // insert forecast for today and tomorrow
+    let today = now.with_timezone(&tz).date_naive();
+    let current = match props.get("temp") {
+        Some(Value::Number { value, .. }) => Some(*value),
+        _ => None,
+    };
+    for (name, date) in [("today", Some(today)), ("tomorrow", today.succ_opt())] {
+        let key = |field: &str| format!("forecast.{}.{}", name, field);
+        match date.and_then(|date| daily(forecast, &tz, date)) {
+            Some(day) => {
+                // the current temperature belongs to today's range too
+                let (min, max) = match (name, current) {
+                    ("today", Some(current)) => (day.min.min(current), day.max.max(current)),
+                    _ => (day.min, day.max),
+                };
+                props.insert(&key("temp_min"), Value::number(min, temp, Some(0)));
+                props.insert(&key("temp_max"), Value::number(max, temp, Some(0)));
+                props.insert(
+                    &key("icon"),
+                    Value::icon(icons.weather(&day.noon.weather[0])),
+                );
+                props.insert(
+                    &key("description"),
+                    Value::text(&day.noon.weather[0].description),
+                );
+            }
+            None => {
+                for field in ["temp_min", "temp_max", "icon", "description"] {
+                    props.remove(&key(field));
+                }
+            }
+        }
+    }
+    // insert compact forecast of the next days
+    let strip: Vec<String> = today
+        .iter_days()
+        .take(days)
+        .filter_map(|date| daily(forecast, &tz, date))
+        .map(|day| {
+            format!(
+                "{}{}{}/{}",
+                &day.date.format("%a").to_string()[..2],
+                icons.weather(&day.noon.weather[0]),
+                Value::number(day.min, temp, Some(0)),
+                Value::number(day.max, temp, Some(0))
+            )
+        })
+        .collect();
+    match strip.is_empty() {
+        true => props.remove("forecast_strip"),
+        false => props.insert("forecast_strip", Value::text(&strip.join(" "))),
+    }
+}
+
+/// aggregated forecast of a single day
+struct Day<'a> {
+    /// local date of the day
+    date: NaiveDate,
+    /// minimum temperature of the day
+    min: f64,
+    /// maximum temperature of the day
+    max: f64,
+    /// entry which is closest to noon
+    noon: &'a Entry,
+}
+
+/// aggregate all forecast entries of a day
+/// #### Parameters
+/// - `forecast`: latest 5 day / 3 hour forecast
+/// - `tz`: timezone of the forecast location
+/// - `date`: local date of the day
+/// #### Return value
+/// - ⇒ `Some(Day)`: aggregated forecast of the day
+/// - ⇒ `None`: forecast contains no entries of that day
+fn daily<'a>(forecast: &'a Forecast, tz: &FixedOffset, date: NaiveDate) -> Option<Day<'a>> {
+    let time = |dt: i64| Utc.timestamp_opt(dt, 0).unwrap().with_timezone(tz);
+    let entries: Vec<&Entry> = forecast
+        .list
+        .iter()
+        .filter(|e| time(e.dt).date_naive() == date)
+        .collect();
+    // take icon and description from the entry which is closest to noon
+    let noon = entries
+        .iter()
+        .min_by_key(|e| (time(e.dt).hour() as i64 - 12).abs())?;
+    return Some(Day {
+        date,
+        min: entries
+            .iter()
+            .map(|e| e.main.temp_min)
+            .fold(f64::INFINITY, f64::min),
+        max: entries
+            .iter()
+            .map(|e| e.main.temp_max)
+            .fold(f64::NEG_INFINITY, f64::max),
+        noon,
+    });
+}
+
+/// update properties map with new air pollution data
+/// #### Parameters
+/// - `props`: property map to add data into
+/// - `air`: current air pollution
+/// - `icons`: icons of the selected theme
+/// #### Return value
+/// - Air Quality Index (1 = Good … 5 = Very Poor) or `0` if unknown
+pub fn get_air(props: &mut Properties, air: &AirPollution, icons: &Icons) -> u8 {
+    let keys = ["aqi", "aqi_icon", "pm2_5", "pm10", "o3", "no2"];
+    let entry = match air.list.first() {
+        Some(entry) => entry,
+        None => {
+            for key in keys {
+                props.remove(key);
+            }
+            return 0;
+        }
+    };
+    let aqi = entry.main.aqi;
+    props.insert("aqi", Value::number(aqi as f64, Unit::None, None));
+    props.insert("aqi_icon", Value::icon(icons.risk(aqi as usize)));
+    let c = &entry.components;
+    let components = [
+        ("pm2_5", c.pm2_5),
+        ("pm10", c.pm10),
+        ("o3", c.o3),
+        ("no2", c.no2),
+    ];
+    for (key, value) in components {
+        props.insert(
+            key,
+            Value::number(value, Unit::MicrogramPerCubicMeter, Some(0)),
+        );
+    }
+    return aqi;
+}
+
+/// update properties map with new One Call API data
+/// #### Parameters
+/// - `props`: property map to add data into
+/// - `onecall`: latest One Call API data
+/// - `icons`: icons of the selected theme
+pub fn get_onecall(props: &mut Properties, onecall: &OneCall, icons: &Icons) {
+    match onecall.current {
+        Some(ref current) => {
+            // risk categories of the WHO
+            let (level, risk) = match current.uvi {
+                uvi if uvi < 2.5 => ("low", 1),
+                uvi if uvi < 5.5 => ("moderate", 2),
+                uvi if uvi < 7.5 => ("high", 3),
+                uvi if uvi < 10.5 => ("very high", 4),
+                _ => ("extreme", 5),
+            };
+            props.insert("uv", Value::number(current.uvi, Unit::None, Some(0)));
+            props.insert("uv_level", Value::text(level));
+            props.insert("uv_icon", Value::icon(icons.risk(risk)));
+        }
+        None => {
+            for key in ["uv", "uv_level", "uv_icon"] {
+                props.remove(key);
+            }
+        }
+    }
+    // the number of alerts is always displayed (also if there is none)
     props.insert(
-        "{speed_unit}",
-        match units {
-            "standard" => "m/s",
-            "metric" => "m/s",
-            "imperial" => "mi/h",
-            _ => "",
+        "alert_count",
+        Value::number(onecall.alerts.len() as f64, Unit::None, None),
+    );
+    match onecall.alerts.first() {
+        Some(alert) => {
+            props.insert("alert", Value::text(&alert.event));
+            props.insert("alert_icon", Value::icon(icons.get("alert")));
+        }
+        None => {
+            props.remove("alert");
+            props.remove("alert_icon");
         }
-        .to_string(),
+    }
+}
+
+/// update properties map with sunrise, sunset and daylight
+/// #### Parameters
+/// - `props`: property map to add data into
+/// - `current`: latest weather update
+/// - `icons`: icons of the selected theme
+/// - `now`: current time
+pub fn get_sun(
+    props: &mut Properties,
+    current: &openweathermap::CurrentWeather,
+    icons: &Icons,
+    now: DateTime<Utc>,
+) {
+    let keys = ["sunrise", "sunset", "daylight", "daylight_left", "sun_next"];
+    // there is no sunrise or sunset during polar day or night
+    if current.sys.sunrise == 0 || current.sys.sunset == 0 {
+        for key in keys {
+            props.remove(key);
+        }
+        return;
+    }
+    let tz =
+        FixedOffset::east_opt(current.timezone as i32).unwrap_or(FixedOffset::east_opt(0).unwrap());
+    let sunrise = Utc.timestamp_opt(current.sys.sunrise, 0).unwrap();
+    let sunset = Utc.timestamp_opt(current.sys.sunset, 0).unwrap();
+    props.insert("sunrise", Value::Time(sunrise.with_timezone(&tz)));
+    props.insert("sunset", Value::Time(sunset.with_timezone(&tz)));
+    props.insert("daylight", Value::Span(sunset - sunrise));
+    let (icon, next) = if now < sunrise {
+        (icons.get("sunrise"), sunrise)
+    } else if now < sunset {
+        (icons.get("sunset"), sunset)
+    } else {
+        // sunrise of tomorrow will be about the same time
+        (icons.get("sunrise"), sunrise + chrono::Duration::days(1))
+    };
+    match now >= sunrise && now < sunset {
+        true => props.insert("daylight_left", Value::Span(sunset - now)),
+        false => props.remove("daylight_left"),
+    }
+    props.insert(
+        "sun_next",
+        Value::Text(format!("{} in {}", icon, Value::Span(next - now))),
     );
 }
+
+/// update properties map with moon phase, moonrise and moonset
+/// #### Parameters
+/// - `props`: property map to add data into
+/// - `current`: latest weather update (to get location and timezone from)
+/// - `icons`: icons of the selected theme
+/// - `now`: current time
+pub fn get_moon(
+    props: &mut Properties,
+    current: &openweathermap::CurrentWeather,
+    icons: &Icons,
+    now: DateTime<Utc>,
+) {
+    let (phase, illumination) = moon::phase(now);
+    // phase index from new moon (0) over full moon (4) to waning crescent (7)
+    let index = (phase * 8.0).round() as usize % 8;
+    let names = [
+        "new moon",
+        "waxing crescent",
+        "first quarter",
+        "waxing gibbous",
+        "full moon",
+        "waning gibbous",
+        "last quarter",
+        "waning crescent",
+    ];
+    // the moon is seen upside down from the southern hemisphere
+    let icon = match current.coord.lat < 0.0 {
+        true => icons.moon(8 - index),
+        false => icons.moon(index),
+    };
+    props.insert("moon_icon", Value::icon(icon));
+    props.insert("moon_phase", Value::text(names[index]));
+    props.insert(
+        "moon_illumination",
+        Value::number(illumination * 100.0, Unit::Percent, Some(0)),
+    );
+    // moonrise and moonset of the current day at the location
+    let tz =
+        FixedOffset::east_opt(current.timezone as i32).unwrap_or(FixedOffset::east_opt(0).unwrap());
+    let start = now
+        .with_timezone(&tz)
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_local_timezone(tz)
+        .unwrap()
+        .with_timezone(&Utc);
+    let (rise, set) = moon::rise_set(start, current.coord.lat, current.coord.lon);
+    for (key, time) in [("moonrise", rise), ("moonset", set)] {
+        match time {
+            Some(time) => props.insert(key, Value::Time(time.with_timezone(&tz))),
+            None => props.remove(key),
+        }
+    }
+}
+
+/// update properties map with the precipitation nowcast of the next hour
+/// #### Parameters
+/// - `props`: property map to add data into
+/// - `onecall`: latest One Call API data
+/// - `now`: current time
+/// #### Return value
+/// - ⇒ `Some(minutes)`: minutes until rain starts (`0` if it is raining)
+/// - ⇒ `None`: no rain within the nowcast or no nowcast available
+pub fn get_nowcast(props: &mut Properties, onecall: &OneCall, now: DateTime<Utc>) -> Option<i64> {
+    // forecasted minutes from the current minute on
+    let minutes: Vec<_> = onecall
+        .minutely
+        .iter()
+        .filter(|m| m.dt > now.timestamp() - 60)
+        .collect();
+    let last = match minutes.last() {
+        Some(last) => last,
+        None => {
+            props.remove("rain_in");
+            props.remove("rain_nowcast");
+            return None;
+        }
+    };
+    let until = |dt: i64| (dt - now.timestamp()).max(0) / 60;
+    let span = |m: i64| match m >= 60 {
+        true => "1h".to_string(),
+        false => format!("{} min", m),
+    };
+    let wet = |m: &Minutely| m.precipitation > 0.0;
+    let start = minutes.iter().find(|m| wet(m)).map(|m| until(m.dt));
+    let text = match start {
+        Some(0) => match minutes.iter().find(|m| !wet(m)) {
+            Some(stop) => format!("rain stops in {}", span(until(stop.dt))),
+            None => format!("rain for {}", span(until(last.dt) + 1)),
+        },
+        Some(start) => format!("rain in {}", span(start)),
+        None => format!("dry for {}", span(until(last.dt) + 1)),
+    };
+    props.insert("rain_in", Value::text(&text));
+    // one bar per 5 minutes showing the heaviest precipitation within
+    let bars = ["▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+    let limits = [0.0, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0];
+    let sparkline: String = minutes
+        .chunks(5)
+        .map(|chunk| {
+            let max = chunk.iter().map(|m| m.precipitation).fold(0.0, f64::max);
+            bars[limits.iter().filter(|limit| max > **limit).count()]
+        })
+        .collect();
+    props.insert("rain_nowcast", Value::icon(&sparkline));
+    return start;
+}